@@ -1,18 +1,21 @@
 use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::character::complete::{multispace1, u8};
-use nom::combinator::{opt, value, verify};
+use nom::bytes::complete::{tag, take_till1};
+use nom::character::complete::{multispace1, u32, u8};
+use nom::combinator::{map, opt, value, verify};
 use nom::sequence::{preceded, tuple};
 use nom::IResult;
 
 use super::Game;
+use super::SearchLimits;
+use super::Setting;
 use super::Time;
 
 #[derive(Clone)]
 pub enum Command {
     Handshake(u8),
-    Move(Game, Option<Time>),
+    Move(Game, Option<Time>, SearchLimits),
     Identify,
+    Option(Setting),
     Quit,
 }
 
@@ -21,21 +24,99 @@ fn parse_win_length(input: &str) -> IResult<&str, u8> {
 }
 
 fn parse_move(input: &str) -> IResult<&str, Command> {
-    let (remaining, (mut game, time, win_length)) = preceded(
+    let (remaining, (mut game, time, win_length, limits)) = preceded(
         tuple((tag("move"), multispace1)),
         tuple((
             Game::parse,
             opt(preceded(multispace1, Time::parse)),
             opt(preceded(multispace1, parse_win_length)),
+            SearchLimits::parse,
         )),
     )(input)?;
-    match win_length {
-        Some(win_length) => {
-            game.set_win_length(win_length);
-        }
-        _ => {}
+    if let Some(win_length) = win_length {
+        game.set_win_length(win_length);
     }
-    Ok((remaining, Command::Move(game, time)))
+    Ok((remaining, Command::Move(game, time, limits)))
+}
+
+/// Parses `generate <path> <game> [win-length <n>]`, given on the command line
+/// to write the solution database for every game reachable from the one given.
+pub fn parse_generate(input: &str) -> IResult<&str, (String, Game)> {
+    let (remaining, (path, mut game, win_length)) = preceded(
+        tuple((tag("generate"), multispace1)),
+        tuple((
+            take_till1(char::is_whitespace),
+            preceded(multispace1, Game::parse),
+            opt(preceded(multispace1, parse_win_length)),
+        )),
+    )(input)?;
+    if let Some(win_length) = win_length {
+        game.set_win_length(win_length);
+    }
+    Ok((remaining, (path.to_string(), game)))
+}
+
+/// How to build an opening book, given on the command line as `book <path>
+/// <records> <game> [win-length <n>] [self-play <n>] [import <path>] [plies
+/// <n>] [min-games <n>] [min-score <n>]` with the limits on self-play moves
+/// after it. The records of every game seen so far are kept between runs so
+/// the book can be rebuilt from all of them.
+#[derive(Clone)]
+pub struct BookOptions {
+    pub book: String,
+    pub records: String,
+    pub game: Game,
+    pub self_play: u32,
+    pub import: Option<String>,
+    pub plies: u8,
+    pub min_games: u32,
+    pub min_score: u8,
+    pub limits: SearchLimits,
+}
+
+fn parse_option<'a, O>(
+    name: &'static str,
+    parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Option<O>> {
+    opt(preceded(
+        tuple((multispace1, tag(name), multispace1)),
+        parser,
+    ))
+}
+
+pub fn parse_book(input: &str) -> IResult<&str, BookOptions> {
+    let (remaining, (book, records, mut game, win_length)) = preceded(
+        tuple((tag("book"), multispace1)),
+        tuple((
+            take_till1(char::is_whitespace),
+            preceded(multispace1, take_till1(char::is_whitespace)),
+            preceded(multispace1, Game::parse),
+            opt(preceded(multispace1, parse_win_length)),
+        )),
+    )(input)?;
+    let (remaining, (self_play, import, plies, min_games, min_score, limits)) = tuple((
+        parse_option("self-play", u32),
+        parse_option("import", take_till1(char::is_whitespace)),
+        parse_option("plies", u8),
+        parse_option("min-games", u32),
+        parse_option("min-score", verify(u8, |score: &u8| *score <= 100)),
+        SearchLimits::parse,
+    ))(remaining)?;
+    if let Some(win_length) = win_length {
+        game.set_win_length(win_length);
+    }
+    let options = BookOptions {
+        book: book.to_string(),
+        records: records.to_string(),
+        game,
+        self_play: self_play.unwrap_or(0),
+        import: import.map(str::to_string),
+        plies: plies.unwrap_or(8),
+        min_games: min_games.unwrap_or(1),
+        min_score: min_score.unwrap_or(0),
+        limits,
+    };
+    Ok((remaining, options))
 }
 
 fn parse_handshake(input: &str) -> IResult<&str, Command> {
@@ -52,7 +133,81 @@ impl Command {
             parse_handshake,
             value(Command::Identify, tag("identify")),
             parse_move,
+            map(
+                preceded(tuple((tag("option"), multispace1)), Setting::parse),
+                Command::Option,
+            ),
             value(Command::Quit, tag("quit")),
         ))(input)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parses_move_with_limits() -> Result<()> {
+        let (remaining, command) =
+            Command::parse("move 3_/3_/3_ x max-depth 4 max-nodes 5000 max-time ms:250")?;
+        assert_eq!(remaining, "");
+        let Command::Move(game, time, limits) = command else {
+            panic!("not a move");
+        };
+        assert_eq!(game.win_length, 3);
+        assert!(time.is_none());
+        assert_eq!(
+            limits,
+            SearchLimits {
+                depth: Some(4),
+                nodes: Some(5000),
+                time: Some(std::time::Duration::from_millis(250)),
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parses_move_with_win_length_and_limits() -> Result<()> {
+        let (remaining, command) =
+            Command::parse("move 5_/5_/5_/5_/5_ x time ms:300 win-length 4 max-nodes 100")?;
+        assert_eq!(remaining, "");
+        let Command::Move(game, time, limits) = command else {
+            panic!("not a move");
+        };
+        assert_eq!(game.win_length, 4);
+        assert!(time.is_some());
+        assert_eq!(
+            limits,
+            SearchLimits {
+                nodes: Some(100),
+                ..SearchLimits::default()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parses_book_options() -> Result<()> {
+        let (remaining, options) = parse_book(
+            "book book.txt records.txt 3_/3_/3_ x self-play 10 plies 4 min-score 40 max-nodes 500",
+        )?;
+        assert_eq!(remaining, "");
+        assert_eq!(options.self_play, 10);
+        assert_eq!(options.import, None);
+        assert_eq!(options.plies, 4);
+        assert_eq!(options.min_games, 1);
+        assert_eq!(options.min_score, 40);
+        assert_eq!(options.limits.nodes, Some(500));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parses_option() -> Result<()> {
+        let (_, command) = Command::parse("option seed 42")?;
+        assert!(matches!(command, Command::Option(Setting::Seed(42))));
+        Ok(())
+    }
+}
@@ -11,3 +11,11 @@ impl Duration {
         Ok((remaining, Duration::Milliseconds(ms)))
     }
 }
+
+impl From<&Duration> for std::time::Duration {
+    fn from(duration: &Duration) -> Self {
+        match duration {
+            Duration::Milliseconds(ms) => std::time::Duration::from_millis(*ms),
+        }
+    }
+}
@@ -8,7 +8,11 @@ use nom::{
 };
 use rustc_hash::FxHashSet;
 
-use super::{zobrist::zobrist, Cell, Position, Side};
+use super::{
+    windows::{windows, Windows},
+    zobrist::zobrist,
+    Cell, Position, Side,
+};
 
 #[derive(Clone, Debug)]
 pub struct Game {
@@ -19,6 +23,20 @@ pub struct Game {
     pub size: u8,
     pub win_length: u8,
     pub hash: u64,
+    windows: &'static Windows,
+    /// The stones of each side in every run, with an unplayable cell counting
+    /// as a stone of both.
+    counts: Vec<[u8; 2]>,
+    /// The runs that have stones of at most one side, so still winnable.
+    open: usize,
+}
+
+#[inline(always)]
+fn index(side: &Side) -> usize {
+    match side {
+        Side::X => 0,
+        Side::O => 1,
+    }
 }
 
 fn parse_count(input: &str) -> IResult<&str, u8> {
@@ -29,9 +47,9 @@ fn parse_count(input: &str) -> IResult<&str, u8> {
     }
 }
 
-pub fn parse_board(
-    input: &str,
-) -> IResult<&str, (Vec<Vec<Cell>>, Vec<Position>, FxHashSet<Position>, u8, u64)> {
+type Board = (Vec<Vec<Cell>>, Vec<Position>, FxHashSet<Position>, u8, u64);
+
+pub fn parse_board(input: &str) -> IResult<&str, Board> {
     let mut moves: Vec<Position> = Vec::new();
     let mut playable: FxHashSet<Position> = FxHashSet::default();
     let mut cells: Vec<Vec<Cell>> = Vec::new();
@@ -41,13 +59,12 @@ pub fn parse_board(
     let usize = groups.len();
     let size = usize as u8;
     let zobrist = zobrist(size);
-    let mut row = 0u8;
-    for group in &groups {
+    for (row, group) in groups.iter().enumerate() {
         let mut vec: Vec<Cell> = Vec::with_capacity(usize);
         let mut column = 0u8;
         for (count, cell) in group {
             for _ in 0..*count {
-                let position = Position(row as u8, column as u8);
+                let position = Position(row as u8, column);
                 match cell {
                     Cell::Playable => {
                         playable.insert(position);
@@ -65,9 +82,8 @@ pub fn parse_board(
             }
         }
         cells.push(vec);
-        row += 1;
     }
-    return Ok((remaining, (cells, moves, playable, size, hash)));
+    Ok((remaining, (cells, moves, playable, size, hash)))
 }
 
 impl Game {
@@ -75,26 +91,68 @@ impl Game {
         let (remaining, ((cells, played, playable, size, mut hash), side_to_play)) =
             separated_pair(parse_board, multispace1, Side::parse)(input)?;
         hash ^= zobrist(size).side(&side_to_play);
-        return Ok((
-            remaining,
-            Game {
-                cells,
-                size,
-                moves: played,
-                playable,
-                side_to_play,
-                win_length: size,
-                hash,
-            },
-        ));
-    }
-
-    pub(crate) fn set_win_length(&mut self, win_length: u8) -> () {
+        let mut game = Game {
+            cells,
+            size,
+            moves: played,
+            playable,
+            side_to_play,
+            win_length: size,
+            hash,
+            windows: windows(size, size),
+            counts: vec![],
+            open: 0,
+        };
+        game.count();
+        Ok((remaining, game))
+    }
+
+    pub(crate) fn set_win_length(&mut self, win_length: u8) {
         self.win_length = win_length;
+        self.windows = windows(self.size, win_length);
+        self.count();
+    }
+
+    /// Counts the stones in every run from scratch.
+    fn count(&mut self) {
+        self.counts = vec![[0, 0]; self.windows.len()];
+        for (row, cells) in self.cells.iter().enumerate() {
+            for (column, cell) in cells.iter().enumerate() {
+                let sides: &[usize] = match cell {
+                    Cell::Playable => &[],
+                    Cell::Played(side) => &[index(side)],
+                    Cell::Unplayable => &[0, 1],
+                };
+                let position = Position(row as u8, column as u8);
+                for &window in self.windows.through(&position) {
+                    for &side in sides {
+                        self.counts[window as usize][side] += 1;
+                    }
+                }
+            }
+        }
+        self.open = self
+            .counts
+            .iter()
+            .filter(|[x, o]| *x == 0 || *o == 0)
+            .count();
+    }
+
+    /// The winning lines of the board for the number in a row that wins.
+    pub fn windows(&self) -> &'static Windows {
+        self.windows
+    }
+
+    /// Whether either side can still get a line, however the game goes on.
+    /// Once neither can the game is a draw, often long before the board is
+    /// full, and sooner still on boards with holes.
+    #[inline(always)]
+    pub fn winnable(&self) -> bool {
+        self.open > 0
     }
 
     pub fn play(&mut self, position: &Position) {
-        if !self.playable.contains(&position) {
+        if !self.playable.contains(position) {
             return;
         }
         let side = self.side_to_play.clone();
@@ -106,8 +164,16 @@ impl Game {
         self.hash ^= zobrist.side(&side);
         self.hash ^= zobrist.side(&other);
         self.hash ^= zobrist.mov(&(position.clone(), cell));
-        self.playable.remove(&position);
+        self.playable.remove(position);
         self.moves.push(position.clone());
+        let (mine, theirs) = (index(&side), index(&other));
+        for &window in self.windows.through(position) {
+            let count = &mut self.counts[window as usize];
+            count[mine] += 1;
+            if count[mine] == 1 && count[theirs] > 0 {
+                self.open -= 1;
+            }
+        }
         self.side_to_play = other;
     }
 
@@ -124,6 +190,14 @@ impl Game {
                 self.hash ^= zobrist.side(&side);
                 self.hash ^= zobrist.side(&other);
                 self.hash ^= zobrist.mov(&(position.clone(), cell));
+                let (mine, theirs) = (index(&other), index(&side));
+                for &window in self.windows.through(&position) {
+                    let count = &mut self.counts[window as usize];
+                    if count[mine] == 1 && count[theirs] > 0 {
+                        self.open += 1;
+                    }
+                    count[mine] -= 1;
+                }
                 self.playable.insert(position);
                 self.side_to_play = other;
             }
@@ -321,6 +395,29 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_board_with_every_line_blocked_is_not_winnable() -> Result<()> {
+        let (_, open) = Game::parse("x_o/_._/o2_ x")?;
+        let (_, blocked) = Game::parse("x_o/_._/o_x x")?;
+        assert!(open.winnable());
+        assert!(!blocked.winnable());
+        Ok(())
+    }
+
+    #[test]
+    fn test_play_and_undo_keep_winnable_lines() -> Result<()> {
+        let (_, mut game) = Game::parse("x_o/_._/o2_ x")?;
+        game.play(&Position(2, 2));
+        assert!(!game.winnable());
+        game.undo();
+        assert!(game.winnable());
+        game.set_win_length(2);
+        assert!(game.winnable());
+        game.play(&Position(2, 2));
+        assert!(game.winnable());
+        Ok(())
+    }
+
     #[test]
     #[ignore]
     fn test_parse_takes_little_time_for_15x15_board() -> Result<()> {
@@ -0,0 +1,111 @@
+use std::time::Instant;
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{multispace1, u64, u8},
+    combinator::map,
+    multi::many0,
+    sequence::{preceded, tuple},
+    IResult,
+};
+
+use super::Duration;
+
+/// Bounds on a search that hold whatever the time control, for repeatable
+/// runs and for weaker opponents.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchLimits {
+    pub depth: Option<u8>,
+    pub nodes: Option<u64>,
+    pub time: Option<std::time::Duration>,
+}
+
+#[derive(Clone, Debug)]
+enum Limit {
+    Depth(u8),
+    Nodes(u64),
+    Time(Duration),
+}
+
+fn parse_limit(input: &str) -> IResult<&str, Limit> {
+    alt((
+        map(
+            preceded(tuple((tag("max-depth"), multispace1)), u8),
+            Limit::Depth,
+        ),
+        map(
+            preceded(tuple((tag("max-nodes"), multispace1)), u64),
+            Limit::Nodes,
+        ),
+        map(
+            preceded(tuple((tag("max-time"), multispace1)), Duration::parse),
+            Limit::Time,
+        ),
+    ))(input)
+}
+
+impl SearchLimits {
+    /// When to stop, at the end of the budget or of the hard time limit,
+    /// whichever comes first.
+    pub fn deadline(&self, start: Instant, budget: Option<std::time::Duration>) -> Option<Instant> {
+        match (budget, self.time) {
+            (Some(budget), Some(time)) => Some(start + budget.min(time)),
+            (budget, time) => budget.or(time).map(|duration| start + duration),
+        }
+    }
+
+    /// Parses the limits given, each preceded by whitespace and in any order,
+    /// the last one winning when a limit is given twice.
+    pub fn parse(input: &str) -> IResult<&str, SearchLimits> {
+        let (remaining, limits) = many0(preceded(multispace1, parse_limit))(input)?;
+        let mut result = SearchLimits::default();
+        for limit in limits {
+            match limit {
+                Limit::Depth(depth) => result.depth = Some(depth),
+                Limit::Nodes(nodes) => result.nodes = Some(nodes),
+                Limit::Time(duration) => result.time = Some((&duration).into()),
+            }
+        }
+        Ok((remaining, result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parses_no_limits() -> Result<()> {
+        let (remaining, limits) = SearchLimits::parse("")?;
+        assert_eq!(remaining, "");
+        assert_eq!(limits, SearchLimits::default());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parses_limits_in_any_order() -> Result<()> {
+        let (remaining, limits) =
+            SearchLimits::parse(" max-nodes 5000 max-time ms:250 max-depth 4")?;
+        assert_eq!(remaining, "");
+        assert_eq!(
+            limits,
+            SearchLimits {
+                depth: Some(4),
+                nodes: Some(5000),
+                time: Some(std::time::Duration::from_millis(250)),
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_leaves_unknown_input() -> Result<()> {
+        let (remaining, limits) = SearchLimits::parse(" max-depth 3 ponder")?;
+        assert_eq!(remaining, " ponder");
+        assert_eq!(limits.depth, Some(3));
+        Ok(())
+    }
+}
@@ -2,16 +2,24 @@ pub mod cell;
 pub mod command;
 pub mod duration;
 pub mod game;
+pub mod limits;
 pub mod position;
+pub mod seed;
+pub mod setting;
 pub mod side;
 pub mod time;
+pub mod windows;
 pub mod zobrist;
 
 pub use cell::*;
 pub use command::*;
 pub use duration::*;
 pub use game::*;
+pub use limits::*;
 pub use position::*;
+pub use seed::*;
+pub use setting::*;
 pub use side::*;
 pub use time::*;
+pub use windows::*;
 pub use zobrist::*;
@@ -1,5 +1,13 @@
 use std::fmt::{Debug, Display};
 
+use nom::{
+    character::complete::{satisfy, u8},
+    combinator::{map_opt, recognize},
+    multi::many1_count,
+    sequence::pair,
+    IResult,
+};
+
 type Row = u8;
 type Column = u8;
 
@@ -10,8 +18,8 @@ fn column(mut index: Column) -> String {
     let mut result = String::new();
     loop {
         let remainder = index % 26;
-        result.insert(0, (97 + remainder as u8) as char);
-        index = index / 26;
+        result.insert(0, (97 + remainder) as char);
+        index /= 26;
         if index == 0 {
             break;
         }
@@ -31,3 +39,50 @@ impl Display for Position {
         write!(f, "{}{}", column(self.1), self.0 + 1)
     }
 }
+
+impl Position {
+    /// Parses a position as it is written, the column in letters and the row
+    /// counted from one, like `c2` or `aa27`.
+    pub fn parse(input: &str) -> IResult<&str, Position> {
+        map_opt(
+            pair(
+                recognize(many1_count(satisfy(|c| c.is_ascii_lowercase()))),
+                u8,
+            ),
+            |(letters, row): (&str, u8)| {
+                let column = letters.bytes().try_fold(0u16, |acc, c| {
+                    acc.checked_mul(26)?.checked_add((c - b'a' + 1) as u16)
+                })?;
+                let column = u8::try_from(column.checked_sub(1)?).ok()?;
+                Some(Position(row.checked_sub(1)?, column))
+            },
+        )(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parses_what_it_displays() -> Result<()> {
+        for position in [
+            Position(0, 0),
+            Position(1, 2),
+            Position(14, 25),
+            Position(26, 26),
+        ] {
+            let written = position.to_string();
+            let (remaining, parsed) = Position::parse(&written).expect("parsed");
+            assert_eq!((remaining, parsed), ("", position));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_row_zero() {
+        assert!(Position::parse("a0").is_err());
+    }
+}
@@ -0,0 +1,156 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+static SEED: AtomicU64 = AtomicU64::new(0);
+
+/// Bumped every time the seed is set, and zero until it first is.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Numbers the random sources in the order they are made, so that each
+/// draws a different stream from the same seed.
+static STREAMS: AtomicU64 = AtomicU64::new(0);
+
+/// Seeds every random source of the engine, the ones already made included,
+/// so that the same seed and node limits make the same moves in every run.
+///
+/// The zobrist tables take the seed in effect when a game of their size is
+/// first hashed, so it has to be set before then to draw them.
+pub fn set_seed(seed: u64) {
+    SEED.store(seed, Ordering::SeqCst);
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// The seed last set, if any.
+pub fn seed() -> Option<u64> {
+    match GENERATION.load(Ordering::SeqCst) {
+        0 => None,
+        _ => Some(SEED.load(Ordering::SeqCst)),
+    }
+}
+
+/// A random source that draws from the global seed once one is set, and
+/// from the operating system until then.
+#[derive(Clone, Debug)]
+pub struct Seeded {
+    stream: u64,
+    generation: u64,
+    private: bool,
+    rng: StdRng,
+}
+
+impl Default for Seeded {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Seeded {
+    pub fn new() -> Self {
+        Self::with_stream(STREAMS.fetch_add(1, Ordering::SeqCst))
+    }
+
+    /// A source with a stream of its own choosing instead of the next one,
+    /// the same for every source made with it.
+    pub fn with_stream(stream: u64) -> Self {
+        let mut seeded = Self {
+            stream,
+            generation: 0,
+            private: false,
+            rng: StdRng::from_entropy(),
+        };
+        seeded.sync();
+        seeded
+    }
+
+    /// A source drawn from the seed given alone, which setting the global
+    /// seed leaves as it is, for tests that run next to ones that set it.
+    #[cfg(test)]
+    pub fn private(seed: u64) -> Self {
+        Self {
+            stream: 0,
+            generation: 0,
+            private: true,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Starts over from the global seed if it changed since the last draw.
+    #[inline(always)]
+    fn sync(&mut self) {
+        let generation = GENERATION.load(Ordering::Relaxed);
+        if generation != self.generation && !self.private {
+            self.generation = generation;
+            let seed = SEED.load(Ordering::SeqCst);
+            self.rng =
+                StdRng::seed_from_u64(seed ^ self.stream.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        }
+    }
+}
+
+impl RngCore for Seeded {
+    fn next_u32(&mut self) -> u32 {
+        self.sync();
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.sync();
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.sync();
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.sync();
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// Held by tests that set the seed, so that they do not reseed each other.
+#[cfg(test)]
+pub static SEED_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    fn draw(rng: &mut Seeded) -> Vec<u64> {
+        (0..4).map(|_| rng.next_u64()).collect()
+    }
+
+    #[test]
+    fn test_same_seed_draws_the_same_numbers() {
+        let _lock = SEED_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut first = Seeded::new();
+        let mut second = Seeded::new();
+        set_seed(42);
+        assert_eq!(seed(), Some(42));
+        let drawn = (draw(&mut first), draw(&mut second));
+        assert_ne!(drawn.0, drawn.1);
+        set_seed(42);
+        assert_eq!((draw(&mut first), draw(&mut second)), drawn);
+        set_seed(43);
+        assert_ne!(draw(&mut first), drawn.0);
+    }
+
+    #[test]
+    fn test_private_source_ignores_the_global_seed() {
+        let _lock = SEED_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut expected = StdRng::seed_from_u64(5);
+        let expected: Vec<u64> = (0..8).map(|_| expected.next_u64()).collect();
+        let mut private = Seeded::private(5);
+        let mut drawn = draw(&mut private);
+        set_seed(42);
+        drawn.extend(draw(&mut private));
+        assert_eq!(drawn, expected);
+    }
+}
@@ -0,0 +1,201 @@
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_till1},
+    character::complete::{i64, multispace0, multispace1, u16, u64, u8},
+    combinator::{map, opt, value, verify},
+    multi::many0,
+    sequence::{preceded, terminated, tuple},
+    IResult,
+};
+
+use super::Side;
+
+/// Engine settings that are not part of a game, given on the command line as
+/// `--name value` or over ST3P as `option name value`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Setting {
+    Threads(u16),
+    Ponder(bool),
+    MultiPv(u16),
+    Solutions(String),
+    Book(String),
+    Seed(u64),
+    /// From 0 up to 20 for full strength.
+    Skill(u8),
+    /// For the side given, or for whichever side the engine plays.
+    Contempt(Option<Side>, i64),
+}
+
+fn parse_switch(input: &str) -> IResult<&str, bool> {
+    alt((value(true, tag("on")), value(false, tag("off"))))(input)
+}
+
+impl Setting {
+    fn parse_threads(input: &str) -> IResult<&str, Setting> {
+        map(
+            preceded(tuple((tag("threads"), multispace1)), u16),
+            Setting::Threads,
+        )(input)
+    }
+
+    fn parse_ponder(input: &str) -> IResult<&str, Setting> {
+        map(
+            preceded(tuple((tag("ponder"), multispace1)), parse_switch),
+            Setting::Ponder,
+        )(input)
+    }
+
+    fn parse_multi_pv(input: &str) -> IResult<&str, Setting> {
+        map(
+            preceded(tuple((tag("multipv"), multispace1)), u16),
+            Setting::MultiPv,
+        )(input)
+    }
+
+    fn parse_solutions(input: &str) -> IResult<&str, Setting> {
+        map(
+            preceded(
+                tuple((tag("solutions"), multispace1)),
+                take_till1(char::is_whitespace),
+            ),
+            |path: &str| Setting::Solutions(path.to_string()),
+        )(input)
+    }
+
+    fn parse_book(input: &str) -> IResult<&str, Setting> {
+        map(
+            preceded(
+                tuple((tag("book"), multispace1)),
+                take_till1(char::is_whitespace),
+            ),
+            |path: &str| Setting::Book(path.to_string()),
+        )(input)
+    }
+
+    fn parse_seed(input: &str) -> IResult<&str, Setting> {
+        map(
+            preceded(tuple((tag("seed"), multispace1)), u64),
+            Setting::Seed,
+        )(input)
+    }
+
+    fn parse_skill(input: &str) -> IResult<&str, Setting> {
+        map(
+            preceded(
+                tuple((tag("skill"), multispace1)),
+                verify(u8, |level: &u8| *level <= 20),
+            ),
+            Setting::Skill,
+        )(input)
+    }
+
+    fn parse_contempt(input: &str) -> IResult<&str, Setting> {
+        map(
+            preceded(
+                tuple((tag("contempt"), multispace1)),
+                tuple((opt(terminated(Side::parse, multispace1)), i64)),
+            ),
+            |(side, value)| Setting::Contempt(side, value),
+        )(input)
+    }
+
+    pub fn parse(input: &str) -> IResult<&str, Setting> {
+        alt((
+            Setting::parse_threads,
+            Setting::parse_ponder,
+            Setting::parse_multi_pv,
+            Setting::parse_solutions,
+            Setting::parse_book,
+            Setting::parse_seed,
+            Setting::parse_skill,
+            Setting::parse_contempt,
+        ))(input)
+    }
+
+    pub fn parse_arguments(input: &str) -> IResult<&str, Vec<Setting>> {
+        many0(preceded(tuple((multispace0, tag("--"))), Setting::parse))(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parses_threads() -> Result<()> {
+        let (_, setting) = Setting::parse("threads 4")?;
+        assert_eq!(setting, Setting::Threads(4));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parses_ponder() -> Result<()> {
+        let (_, on) = Setting::parse("ponder on")?;
+        let (_, off) = Setting::parse("ponder off")?;
+        assert_eq!((on, off), (Setting::Ponder(true), Setting::Ponder(false)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parses_multi_pv() -> Result<()> {
+        let (_, setting) = Setting::parse("multipv 3")?;
+        assert_eq!(setting, Setting::MultiPv(3));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parses_arguments() -> Result<()> {
+        let (remaining, settings) = Setting::parse_arguments("--threads 4 --ponder on")?;
+        assert_eq!(settings, vec![Setting::Threads(4), Setting::Ponder(true)]);
+        assert_eq!(remaining, "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_leaves_unknown_arguments() -> Result<()> {
+        let (remaining, settings) = Setting::parse_arguments("--colour blue")?;
+        assert_eq!(settings, vec![]);
+        assert_eq!(remaining, "--colour blue");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parses_solutions_path() -> Result<()> {
+        let (_, setting) = Setting::parse("solutions data/3x3.bin")?;
+        assert_eq!(setting, Setting::Solutions("data/3x3.bin".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parses_book_path() -> Result<()> {
+        let (_, setting) = Setting::parse("book openings.txt")?;
+        assert_eq!(setting, Setting::Book("openings.txt".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parses_seed() -> Result<()> {
+        let (_, setting) = Setting::parse("seed 12345678901")?;
+        assert_eq!(setting, Setting::Seed(12_345_678_901));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parses_skill_up_to_twenty() -> Result<()> {
+        let (_, setting) = Setting::parse("skill 7")?;
+        assert_eq!(setting, Setting::Skill(7));
+        assert!(Setting::parse("skill 21").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parses_contempt_for_engine_or_side() -> Result<()> {
+        let (_, engine) = Setting::parse("contempt 25")?;
+        let (_, side) = Setting::parse("contempt o -10")?;
+        assert_eq!(engine, Setting::Contempt(None, 25));
+        assert_eq!(side, Setting::Contempt(Some(Side::O), -10));
+        Ok(())
+    }
+}
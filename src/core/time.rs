@@ -6,7 +6,14 @@ use nom::{
     IResult,
 };
 
-use super::Duration;
+use super::{Duration, Game};
+
+/// Time kept back from every budget to cover parsing and writing the reply.
+const OVERHEAD: std::time::Duration = std::time::Duration::from_millis(20);
+
+/// The most moves we expect to still have to make when splitting the time
+/// remaining, so that big boards do not starve every move.
+const HORIZON: u32 = 30;
 
 #[derive(Clone, Debug)]
 pub enum Time {
@@ -30,4 +37,54 @@ impl Time {
     pub fn parse(input: &str) -> IResult<&str, Time> {
         alt((Time::parse_remaining, Time::parse_per_move))(input)
     }
+
+    pub fn budget(&self, game: &Game) -> std::time::Duration {
+        match self {
+            Time::PerMove(duration) => std::time::Duration::from(duration).saturating_sub(OVERHEAD),
+            Time::Remaining(duration) => {
+                let moves = (game.playable.len() as u32).div_ceil(2).clamp(1, HORIZON);
+                std::time::Duration::from(duration).saturating_sub(OVERHEAD) / moves
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_budget_per_move_keeps_overhead() -> Result<()> {
+        let (_, game) = Game::parse("3_/3_/3_ x")?;
+        let time = Time::PerMove(Duration::Milliseconds(1000));
+        assert_eq!(time.budget(&game), std::time::Duration::from_millis(980));
+        Ok(())
+    }
+
+    #[test]
+    fn test_budget_remaining_splits_over_moves_left() -> Result<()> {
+        let (_, game) = Game::parse("3_/3_/3_ x")?;
+        let time = Time::Remaining(Duration::Milliseconds(1020));
+        assert_eq!(time.budget(&game), std::time::Duration::from_millis(200));
+        Ok(())
+    }
+
+    #[test]
+    fn test_budget_remaining_caps_moves_left_on_big_boards() -> Result<()> {
+        let (_, game) =
+            Game::parse("15_/15_/15_/15_/15_/15_/15_/15_/15_/15_/15_/15_/15_/15_/15_ x")?;
+        let time = Time::Remaining(Duration::Milliseconds(3020));
+        assert_eq!(time.budget(&game), std::time::Duration::from_millis(100));
+        Ok(())
+    }
+
+    #[test]
+    fn test_budget_does_not_underflow() -> Result<()> {
+        let (_, game) = Game::parse("3_/3_/3_ x")?;
+        let time = Time::PerMove(Duration::Milliseconds(5));
+        assert_eq!(time.budget(&game), std::time::Duration::ZERO);
+        Ok(())
+    }
 }
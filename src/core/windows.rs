@@ -0,0 +1,126 @@
+use std::{
+    collections::hash_map::Entry,
+    sync::{Mutex, OnceLock},
+};
+
+use rustc_hash::FxHashMap;
+
+use super::Position;
+
+/// Every run of as many cells as it takes to win along a row, column or
+/// diagonal of a board, numbered, and the runs each cell is part of. These
+/// are the winning lines of the board for every heuristic and for the game
+/// alike.
+#[derive(Debug)]
+pub struct Windows {
+    size: u8,
+    lines: Vec<Vec<Position>>,
+    through: Vec<Vec<u32>>,
+}
+
+impl Windows {
+    fn new(size: u8, win_length: u8) -> Self {
+        let n = size as i16;
+        let length = win_length as i16;
+        let mut through = vec![vec![]; (size as usize).pow(2)];
+        let mut lines = vec![];
+        // A single cell is a run in every direction, but only one line.
+        let directions = match length {
+            0 => &[][..],
+            1 => &[(0, 1)][..],
+            _ => &[(0, 1), (1, 0), (1, 1), (1, -1)][..],
+        };
+        for &(dr, dc) in directions {
+            for row in 0..n {
+                for column in 0..n {
+                    let (end_row, end_column) =
+                        (row + dr * (length - 1), column + dc * (length - 1));
+                    if !(0..n).contains(&end_row) || !(0..n).contains(&end_column) {
+                        continue;
+                    }
+                    let line: Vec<Position> = (0..length)
+                        .map(|k| Position((row + dr * k) as u8, (column + dc * k) as u8))
+                        .collect();
+                    for Position(row, column) in &line {
+                        let cell = *row as usize * size as usize + *column as usize;
+                        through[cell].push(lines.len() as u32);
+                    }
+                    lines.push(line);
+                }
+            }
+        }
+        Self {
+            size,
+            lines,
+            through,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// The cells of every run, in the order they are numbered.
+    pub fn lines(&self) -> &[Vec<Position>] {
+        &self.lines
+    }
+
+    /// The numbers of the runs the cell is part of.
+    #[inline(always)]
+    pub fn through(&self, Position(row, column): &Position) -> &[u32] {
+        &self.through[*row as usize * self.size as usize + *column as usize]
+    }
+}
+
+type Shared = Mutex<FxHashMap<(u8, u8), &'static Windows>>;
+
+static WINDOWS: OnceLock<Shared> = OnceLock::new();
+
+/// The runs for the board size and win length, worked out once for each.
+pub fn windows(size: u8, win_length: u8) -> &'static Windows {
+    let mut shared = WINDOWS
+        .get_or_init(Shared::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match shared.entry((size, win_length)) {
+        Entry::Occupied(entry) => entry.get(),
+        Entry::Vacant(entry) => entry.insert(Box::leak(Box::new(Windows::new(size, win_length)))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_counts_every_run() {
+        assert_eq!(windows(3, 3).len(), 8);
+        assert_eq!(windows(4, 3).len(), 24);
+        assert_eq!(windows(15, 5).len(), 572);
+    }
+
+    #[test]
+    fn test_lists_runs_through_a_cell() {
+        let windows = windows(3, 3);
+        assert_eq!(windows.through(&Position(1, 1)).len(), 4);
+        assert_eq!(windows.through(&Position(0, 0)).len(), 3);
+        assert_eq!(windows.through(&Position(0, 1)).len(), 2);
+    }
+
+    #[test]
+    fn test_lines_are_the_runs_through_their_cells() {
+        assert_eq!(windows(4, 1).len(), 16);
+        let windows = windows(5, 4);
+        for (index, line) in windows.lines().iter().enumerate() {
+            assert_eq!(line.len(), 4);
+            for position in line {
+                assert!(windows.through(position).contains(&(index as u32)));
+            }
+        }
+    }
+}
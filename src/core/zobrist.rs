@@ -1,8 +1,9 @@
-use memoize::memoize;
-use rand::{rngs::ThreadRng, thread_rng, RngCore};
+use std::sync::OnceLock;
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
 use rustc_hash::{FxBuildHasher, FxHashMap, FxHashSet};
 
-use super::{Cell, Position, Side};
+use super::{seed, Cell, Position, Side};
 
 #[derive(Clone)]
 pub struct Zobrist(FxHashMap<(Position, Cell), u64>, FxHashMap<Side, u64>);
@@ -12,15 +13,15 @@ impl Zobrist {
         if key.1 == Cell::Playable {
             0
         } else {
-            self.0[&key]
+            self.0[key]
         }
     }
 
     pub fn side(&self, side: &Side) -> u64 {
-        self.1[&side]
+        self.1[side]
     }
 
-    pub fn new(size: u8, rng: &mut ThreadRng) -> Self {
+    pub fn new<R: RngCore>(size: u8, rng: &mut R) -> Self {
         let mut used: FxHashSet<u64> = FxHashSet::default();
         let mut moves: FxHashMap<(Position, Cell), u64> =
             FxHashMap::with_capacity_and_hasher((size as usize).pow(2) * 3, FxBuildHasher);
@@ -53,15 +54,37 @@ impl Zobrist {
     }
 }
 
-#[memoize]
-pub fn zobrist(size: u8) -> Zobrist {
-    Zobrist::new(size, &mut thread_rng())
+/// One table per board size, shared by every thread so that they all agree on
+/// the hash of a game.
+static ZOBRIST: [OnceLock<Zobrist>; 256] = [const { OnceLock::new() }; 256];
+
+/// One table per board size drawn from the size alone.
+static STABLE: [OnceLock<Zobrist>; 256] = [const { OnceLock::new() }; 256];
+
+/// The table games of the board size are hashed with, drawn from the global
+/// seed the first time a game of that size is hashed, or the same as the
+/// `stable` one when no seed is set by then. A seed set later leaves it as it
+/// is, as the games and tables hashed with it so far would no longer agree.
+pub fn zobrist(size: u8) -> &'static Zobrist {
+    ZOBRIST[size as usize].get_or_init(|| match seed() {
+        Some(seed) => Zobrist::new(size, &mut StdRng::seed_from_u64(seed ^ size as u64)),
+        None => stable(size).clone(),
+    })
+}
+
+/// The table for the board size drawn from a generator seeded with the size
+/// alone, so that hashes kept in files mean the same game in every run and
+/// under every seed.
+pub fn stable(size: u8) -> &'static Zobrist {
+    STABLE[size as usize]
+        .get_or_init(|| Zobrist::new(size, &mut StdRng::seed_from_u64(size as u64)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use pretty_assertions::assert_eq;
+    use crate::core::{set_seed, SEED_LOCK};
+    use pretty_assertions::{assert_eq, assert_ne};
     use std::time::{Duration, Instant};
 
     #[test]
@@ -72,6 +95,34 @@ mod tests {
         assert!(start.elapsed() < Duration::from_micros(1500));
     }
 
+    #[test]
+    fn test_zobrist_is_shared_between_threads() {
+        let here = zobrist(7).side(&Side::X);
+        let there = std::thread::spawn(|| zobrist(7).side(&Side::X))
+            .join()
+            .expect("joined");
+        assert_eq!(here, there);
+    }
+
+    #[test]
+    fn test_stable_zobrist_is_the_same_in_every_run() {
+        let fresh = Zobrist::new(9, &mut StdRng::seed_from_u64(9));
+        assert_eq!(stable(9).side(&Side::O), fresh.side(&Side::O));
+        let key = (Position(4, 4), Cell::Played(Side::X));
+        assert_eq!(stable(9).mov(&key), fresh.mov(&key));
+    }
+
+    #[test]
+    fn test_zobrist_is_drawn_from_the_seed() {
+        let _lock = SEED_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        set_seed(7);
+        let fresh = Zobrist::new(200, &mut StdRng::seed_from_u64(7 ^ 200));
+        assert_eq!(zobrist(200).side(&Side::X), fresh.side(&Side::X));
+        assert_ne!(zobrist(200).side(&Side::X), stable(200).side(&Side::X));
+    }
+
     #[test]
     fn test_zobrist_for_size_15_returns_0_for_playable_cell() {
         let z = zobrist(15);
@@ -1,29 +1,44 @@
 use nohash_hasher::IntSet;
 
-use crate::core::{zobrist, Game, Position, Zobrist};
+use crate::core::{stable, zobrist, Game, Position, Zobrist};
 
 use super::Hasher;
 
-fn transpose_once<R: Fn(u8, u8, u8) -> u8, C: Fn(u8, u8, u8) -> u8>(
-    game: &Game,
-    zobrist: &Zobrist,
-    row: R,
-    column: C,
-) -> u64 {
+/// Maps a cell of a board of the given size to where it lands after turning
+/// or flipping the board.
+pub type Symmetry = fn(&Position, u8) -> Position;
+
+/// The eight ways to turn or flip a square board onto itself, the identity
+/// first.
+pub const SYMMETRIES: [Symmetry; 8] = [
+    |Position(i, j), _| Position(*i, *j),
+    |Position(i, j), n| Position(*j, n - 1 - i),
+    |Position(i, j), n| Position(n - 1 - i, n - 1 - j),
+    |Position(i, j), n| Position(n - 1 - j, *i),
+    |Position(i, j), n| Position(n - 1 - i, *j),
+    |Position(i, j), n| Position(*i, n - 1 - j),
+    |Position(i, j), _| Position(*j, *i),
+    |Position(i, j), n| Position(n - 1 - j, n - 1 - i),
+];
+
+/// The index of the symmetry that undoes the one at the index given.
+pub fn inverse(symmetry: usize) -> usize {
+    match symmetry {
+        1 => 3,
+        3 => 1,
+        other => other,
+    }
+}
+
+/// The hash of the game with every cell moved by the symmetry.
+fn transpose_once(game: &Game, zobrist: &Zobrist, symmetry: Symmetry) -> u64 {
     let size = game.size;
-    let mut transposed = game.hash;
+    let mut transposed = zobrist.side(&game.side_to_play);
     for i in 0..size {
         for j in 0..size {
             let position = Position(i, j);
-            let transposition = Position(row(i, j, size), column(i, j, size));
-            match (game.get(&position), game.get(&transposition)) {
-                (Some(c), Some(t)) => {
-                    transposed ^= zobrist.mov(&(position.clone(), c.clone()))
-                        ^ zobrist.mov(&(position, t.clone()));
-                    transposed ^=
-                        zobrist.mov(&(transposition.clone(), t)) ^ zobrist.mov(&(transposition, c));
-                }
-                _ => {}
+            if let Some(cell) = game.get(&position) {
+                transposed ^= zobrist.mov(&(symmetry(&position, size), cell));
             }
         }
     }
@@ -35,43 +50,161 @@ pub struct Transposer;
 impl Transposer {
     pub fn transpose(&self, game: &Game, zobrist: &Zobrist) -> Vec<u64> {
         let mut seen: IntSet<u64> = IntSet::default();
+        seen.insert(game.hash);
         let mut transpositions = vec![game.hash];
-        let rotated_90 = transpose_once(game, zobrist, |_, j, _| j, |i, _, n| n - 1 - i);
-        if seen.insert(rotated_90) {
-            transpositions.push(rotated_90);
-        }
-        let rotated_180 = transpose_once(game, zobrist, |i, _, n| n - 1 - i, |_, j, n| n - 1 - j);
-        if seen.insert(rotated_180) {
-            transpositions.push(rotated_180);
-        }
-        let rotated_270 = transpose_once(game, zobrist, |_, j, n| n - 1 - j, |i, _, _| i);
-        if seen.insert(rotated_270) {
-            transpositions.push(rotated_270);
-        }
-        let reflected_horizontal = transpose_once(game, zobrist, |i, _, n| n - 1 - i, |_, j, _| j);
-        if seen.insert(reflected_horizontal) {
-            transpositions.push(reflected_horizontal);
+        for symmetry in &SYMMETRIES[1..] {
+            let transposed = transpose_once(game, zobrist, *symmetry);
+            if seen.insert(transposed) {
+                transpositions.push(transposed);
+            }
         }
-        let reflected_vertical = transpose_once(game, zobrist, |i, _, _| i, |_, j, n| n - 1 - j);
-        if seen.insert(reflected_vertical) {
-            transpositions.push(reflected_vertical);
+        transpositions
+    }
+
+    /// The indices of the symmetries that leave the game as it is, only the
+    /// identity for most games.
+    pub fn symmetries(&self, game: &Game) -> Vec<usize> {
+        let zobrist = zobrist(game.size);
+        let mut symmetries = vec![0];
+        for (index, symmetry) in SYMMETRIES.iter().enumerate().skip(1) {
+            if transpose_once(game, zobrist, *symmetry) == game.hash {
+                symmetries.push(index);
+            }
         }
-        let reflected_diagonal = transpose_once(game, zobrist, |_, j, _| j, |i, _, _| i);
-        if seen.insert(reflected_diagonal) {
-            transpositions.push(reflected_diagonal);
+        symmetries
+    }
+
+    /// The least hash of the game over all its symmetries, the same for every
+    /// game that is only a turn or flip of another, along with the index of
+    /// the symmetry that takes this game to the one with that hash. Hashes are
+    /// taken with the `stable` table whatever the seed, as books and solution
+    /// databases keep them in files.
+    pub fn canonical(&self, game: &Game) -> (u64, usize) {
+        let zobrist = stable(game.size);
+        let mut canonical = (transpose_once(game, zobrist, SYMMETRIES[0]), 0);
+        for (index, symmetry) in SYMMETRIES.iter().enumerate().skip(1) {
+            let transposed = transpose_once(game, zobrist, *symmetry);
+            if transposed < canonical.0 {
+                canonical = (transposed, index);
+            }
         }
-        let reflected_anti =
-            transpose_once(game, zobrist, |_, j, n| n - 1 - j, |i, _, n| n - 1 - i);
-        if seen.insert(reflected_anti) {
-            transpositions.push(reflected_anti);
+        canonical
+    }
+}
+
+/// Whether one of the symmetries takes the first cell onto the second.
+pub fn equivalent(a: &Position, b: &Position, symmetries: &[usize], size: u8) -> bool {
+    symmetries
+        .iter()
+        .any(|&index| SYMMETRIES[index](a, size) == *b)
+}
+
+/// The first of the moves in each set that the symmetries take onto each
+/// other, in the order given.
+pub fn distinct(moves: Vec<Position>, symmetries: &[usize], size: u8) -> Vec<Position> {
+    if symmetries.len() < 2 {
+        return moves;
+    }
+    let mut kept: Vec<Position> = Vec::with_capacity(moves.len());
+    for position in moves {
+        if !kept
+            .iter()
+            .any(|known| equivalent(&position, known, symmetries, size))
+        {
+            kept.push(position);
         }
-        transpositions
     }
+    kept
 }
 
 impl Hasher for Transposer {
     fn hashes(&mut self, game: &crate::core::Game) -> Vec<u64> {
-        let zobrist = zobrist(game.size);
-        self.transpose(game, &zobrist)
+        self.transpose(game, zobrist(game.size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_transpositions_include_turned_board() -> Result<()> {
+        let (_, game) = Game::parse("x3_/4_/4_/_o2_ o")?;
+        let (_, turned) = Game::parse("_o2_/4_/4_/x3_ o")?;
+        let hashes = Transposer.hashes(&game);
+        assert_eq!(hashes.len(), 8);
+        assert!(hashes.contains(&turned.hash));
+        Ok(())
+    }
+
+    #[test]
+    fn test_symmetric_board_has_fewer_transpositions() -> Result<()> {
+        let (_, game) = Game::parse("3_/_x_/3_ o")?;
+        assert_eq!(Transposer.hashes(&game), vec![game.hash]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonical_is_shared_by_symmetric_games() -> Result<()> {
+        let (_, game) = Game::parse("x2_/_o_/3_ x")?;
+        let (_, flipped) = Game::parse("2_x/_o_/3_ x")?;
+        let (_, turned) = Game::parse("3_/_o_/2_x x")?;
+        let (canonical, _) = Transposer.canonical(&game);
+        assert_eq!(Transposer.canonical(&flipped).0, canonical);
+        assert_eq!(Transposer.canonical(&turned).0, canonical);
+        Ok(())
+    }
+
+    #[test]
+    fn test_inverse_undoes_every_symmetry() {
+        let position = Position(1, 2);
+        for (index, symmetry) in SYMMETRIES.iter().enumerate() {
+            let mapped = symmetry(&position, 5);
+            assert_eq!(SYMMETRIES[inverse(index)](&mapped, 5), position);
+        }
+    }
+
+    #[test]
+    fn test_empty_board_has_every_symmetry() -> Result<()> {
+        let (_, empty) = Game::parse("3_/3_/3_ x")?;
+        let (_, corner) = Game::parse("x2_/3_/3_ o")?;
+        assert_eq!(Transposer.symmetries(&empty), (0..8).collect::<Vec<_>>());
+        assert_eq!(Transposer.symmetries(&corner), vec![0, 6]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_distinct_keeps_one_move_per_class() -> Result<()> {
+        let (_, empty) = Game::parse("3_/3_/3_ x")?;
+        let (_, centre) = Game::parse("3_/_x_/3_ o")?;
+        let moves = |game: &Game| {
+            let mut moves: Vec<Position> = game.playable.iter().cloned().collect();
+            moves.sort();
+            moves
+        };
+        assert_eq!(
+            distinct(moves(&empty), &Transposer.symmetries(&empty), 3),
+            vec![Position(0, 0), Position(0, 1), Position(1, 1)]
+        );
+        assert_eq!(
+            distinct(moves(&centre), &Transposer.symmetries(&centre), 3),
+            vec![Position(0, 0), Position(0, 1)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_same_move_on_turned_board_keeps_canonical() -> Result<()> {
+        let (_, mut game) = Game::parse("x3_/4_/4_/_o2_ x")?;
+        let (_, mut turned) = Game::parse("_o2_/4_/4_/x3_ x")?;
+        game.play(&Position(1, 2));
+        turned.play(&Position(2, 2));
+        assert_eq!(
+            Transposer.canonical(&game).0,
+            Transposer.canonical(&turned).0
+        );
+        Ok(())
     }
 }
@@ -1,6 +1,6 @@
 use crate::core::{Game, Side};
 
-use super::{assurance::Assurance, Heuristic};
+use super::{assurance::Assurance, Heuristic, Score};
 
 pub struct Assurer(Box<dyn Heuristic>);
 
@@ -11,15 +11,15 @@ impl Assurer {
 }
 
 impl Heuristic for Assurer {
-    fn score(&mut self, game: &Game) -> i64 {
+    fn score(&mut self, game: &Game) -> Score {
         match Assurance::of(game) {
-            Some(Assurance(Side::X, count)) => {
-                i64::MAX - (game.moves.len() as i64) - (count as i64)
-            }
-            Some(Assurance(Side::O, count)) => {
-                i64::MIN + (game.moves.len() as i64) + (count as i64)
-            }
+            Some(Assurance(Side::X, count)) => Score::won(game.moves.len() + count as usize),
+            Some(Assurance(Side::O, count)) => Score::lost(game.moves.len() + count as usize),
             None => self.0.score(game),
         }
     }
+
+    fn hits(&self) -> u64 {
+        self.0.hits()
+    }
 }
@@ -2,19 +2,22 @@ use nohash_hasher::IntMap;
 
 use crate::{core::Game, hashers::Hasher};
 
-use super::Heuristic;
+use super::{Heuristic, Score};
 
-pub struct Cached(Box<dyn Heuristic>, IntMap<u64, i64>, Box<dyn Hasher>);
+pub struct Cached(Box<dyn Heuristic>, IntMap<u64, Score>, Box<dyn Hasher>, u64);
 impl Cached {
     pub fn new(heuristic: Box<dyn Heuristic>, hasher: Box<dyn Hasher>) -> Self {
-        Self(heuristic, IntMap::default(), hasher)
+        Self(heuristic, IntMap::default(), hasher, 0)
     }
 }
 
 impl Heuristic for Cached {
-    fn score(&mut self, game: &Game) -> i64 {
+    fn score(&mut self, game: &Game) -> Score {
         match self.1.get(&game.hash) {
-            Some(&score) => score,
+            Some(&score) => {
+                self.3 += 1;
+                score
+            }
             None => {
                 let score = self.0.score(game);
                 for key in self.2.hashes(game) {
@@ -24,4 +27,29 @@ impl Heuristic for Cached {
             }
         }
     }
+
+    fn hits(&self) -> u64 {
+        self.3 + self.0.hits()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hashers::Transposer, heuristics::Chance};
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_counts_scores_found_in_the_cache() -> Result<()> {
+        let (_, game) = Game::parse("x2_/3_/3_ o")?;
+        let (_, turned) = Game::parse("2_x/3_/3_ o")?;
+        let mut cached = Cached::new(Box::new(Chance), Box::new(Transposer));
+        cached.score(&game);
+        assert_eq!(cached.hits(), 0);
+        cached.score(&turned);
+        cached.score(&game);
+        assert_eq!(cached.hits(), 2);
+        Ok(())
+    }
 }
@@ -1,27 +1,8 @@
-use std::collections::HashSet;
-
-use memoize::memoize;
 use nohash_hasher::IntMap;
 
-use crate::core::{Cell, Game, Position, Side};
-
-use super::{line::Line, wins::wins, Heuristic};
+use crate::core::{Cell, Game, Side};
 
-#[memoize]
-fn winning_lines(size: u8, win_length: u8) -> Vec<Line> {
-    let mut seen = HashSet::new();
-    let mut lines = vec![];
-    for i in 0..size {
-        for j in 0..size {
-            for line in wins(&Position(i, j), size, win_length) {
-                if seen.insert(line.clone()) {
-                    lines.push(line);
-                }
-            }
-        }
-    }
-    lines
-}
+use super::{line::Line, Heuristic, Score};
 
 fn winnable(line: &Line, game: &Game) -> Option<(Side, u8)> {
     let mut side: Option<Side> = None;
@@ -32,7 +13,7 @@ fn winnable(line: &Line, game: &Game) -> Option<(Side, u8)> {
                 return None;
             }
             Some(Cell::Played(played)) => {
-                if None == side {
+                if side.is_none() {
                     side = Some(played.clone());
                 }
                 if Some(played) != side {
@@ -52,42 +33,41 @@ fn winnable(line: &Line, game: &Game) -> Option<(Side, u8)> {
 pub struct Chance;
 
 impl Heuristic for Chance {
-    fn score(&mut self, game: &Game) -> i64 {
+    fn score(&mut self, game: &Game) -> Score {
         let mut x_win_lengths = IntMap::default();
         let mut o_win_lengths = IntMap::default();
         for i in 0..game.win_length {
             x_win_lengths.insert(i, 0u8);
             o_win_lengths.insert(i, 0u8);
         }
-        for line in winning_lines(game.size, game.win_length) {
-            match winnable(&line, game) {
-                Some((side, count)) => match side {
+        for line in game.windows().lines() {
+            if let Some((side, count)) = winnable(line, game) {
+                match side {
                     Side::X => {
                         if count == game.win_length {
-                            return i64::MAX - (game.moves.len() as i64);
+                            return Score::won(game.moves.len());
                         }
                         x_win_lengths
                             .insert(count, x_win_lengths.get(&count).expect("warmed up") + 1u8);
                     }
                     Side::O => {
                         if count == game.win_length {
-                            return i64::MIN + (game.moves.len() as i64);
+                            return Score::lost(game.moves.len());
                         }
                         o_win_lengths
                             .insert(count, o_win_lengths.get(&count).expect("warmed up") + 1u8);
                     }
-                },
-                None => {}
+                }
             }
         }
         let imminent = game.win_length - 1u8;
         let x_imminent_win_chances = *x_win_lengths.get(&imminent).expect("warmed up");
         let o_imminent_win_chances = *o_win_lengths.get(&imminent).expect("warmed up");
         if game.side_to_play == Side::X && (x_imminent_win_chances > 0u8) {
-            return i64::MAX - (game.moves.len() as i64) - 1;
+            return Score::won(game.moves.len() + 1);
         }
         if game.side_to_play == Side::O && (o_imminent_win_chances > 0u8) {
-            return i64::MIN + (game.moves.len() as i64) + 1;
+            return Score::lost(game.moves.len() + 1);
         }
         match (
             x_imminent_win_chances > 1u8,
@@ -95,10 +75,10 @@ impl Heuristic for Chance {
             game.side_to_play.clone(),
         ) {
             (true, false, Side::O) => {
-                return i64::MAX - (game.moves.len() as i64) - 2;
+                return Score::won(game.moves.len() + 2);
             }
             (false, true, Side::X) => {
-                return i64::MIN + (game.moves.len() as i64) + 2;
+                return Score::lost(game.moves.len() + 2);
             }
             _ => {}
         }
@@ -109,6 +89,6 @@ impl Heuristic for Chance {
             let local_score = (x_wins - o_wins) * 2i64.pow(2 * (i as u32));
             score += local_score;
         }
-        score
+        Score::Value(score)
     }
 }
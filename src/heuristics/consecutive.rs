@@ -1,7 +1,7 @@
 use crate::core::{Cell, Game, Side};
 
 use super::{
-    antis::antis, columns::columns, diagonals::diagonals, line::Line, rows::rows, Heuristic,
+    antis::antis, columns::columns, diagonals::diagonals, line::Line, rows::rows, Heuristic, Score,
 };
 
 pub struct Consecutive;
@@ -48,7 +48,7 @@ impl Consecutive {
 }
 
 impl Heuristic for Consecutive {
-    fn score(&mut self, game: &Game) -> i64 {
+    fn score(&mut self, game: &Game) -> Score {
         let mut score = 0;
         for line in rows(game.size) {
             score += self.score(&line, game);
@@ -62,7 +62,7 @@ impl Heuristic for Consecutive {
         for line in antis(game.size) {
             score += self.score(&line, game);
         }
-        score
+        Score::Value(score)
     }
 }
 
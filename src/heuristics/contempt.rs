@@ -0,0 +1,97 @@
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+
+use crate::core::Side;
+
+use super::Score;
+
+/// How much less than nothing a draw is worth to each side, shared by the
+/// heuristics and searches of an engine so that they all score draws alike.
+/// A side with contempt for draws takes small risks to keep its winning
+/// chances alive, and one with a negative contempt settles for a draw sooner.
+///
+/// It is either set per side, or for whichever side the engine plays, which
+/// only takes effect once a search tells it which side that is.
+#[derive(Debug, Default)]
+pub struct Contempt {
+    x: AtomicI64,
+    o: AtomicI64,
+    engine: AtomicI64,
+    relative: AtomicBool,
+}
+
+impl Contempt {
+    fn of(&self, side: &Side) -> &AtomicI64 {
+        match side {
+            Side::X => &self.x,
+            Side::O => &self.o,
+        }
+    }
+
+    /// The contempt of one side, leaving the other as it is.
+    pub fn set_side(&self, side: &Side, contempt: i64) {
+        self.relative.store(false, Ordering::SeqCst);
+        self.of(side).store(contempt, Ordering::SeqCst);
+    }
+
+    /// The contempt of the side the engine plays, with none for the other.
+    pub fn set_engine(&self, contempt: i64) {
+        self.engine.store(contempt, Ordering::SeqCst);
+        self.relative.store(true, Ordering::SeqCst);
+    }
+
+    /// Tells the contempt which side the engine is about to search for.
+    pub fn play_as(&self, side: &Side) {
+        if self.relative.load(Ordering::SeqCst) {
+            self.of(side)
+                .store(self.engine.load(Ordering::SeqCst), Ordering::SeqCst);
+            self.of(&side.other()).store(0, Ordering::SeqCst);
+        }
+    }
+
+    /// The score of a draw from the point of view of X.
+    #[inline(always)]
+    pub fn draw(&self) -> Score {
+        match (self.o.load(Ordering::Relaxed)).saturating_sub(self.x.load(Ordering::Relaxed)) {
+            0 => Score::Draw,
+            value => Score::Value(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_draw_is_worth_less_to_the_side_with_contempt() {
+        let contempt = Contempt::default();
+        assert_eq!(contempt.draw(), Score::Draw);
+        contempt.set_side(&Side::X, 30);
+        assert_eq!(contempt.draw(), Score::Value(-30));
+        contempt.set_side(&Side::O, 10);
+        assert_eq!(contempt.draw(), Score::Value(-20));
+    }
+
+    #[test]
+    fn test_engine_contempt_follows_the_side_played() {
+        let contempt = Contempt::default();
+        contempt.set_engine(25);
+        assert_eq!(contempt.draw(), Score::Draw);
+        contempt.play_as(&Side::O);
+        assert_eq!(contempt.draw(), Score::Value(25));
+        contempt.play_as(&Side::X);
+        assert_eq!(contempt.draw(), Score::Value(-25));
+    }
+
+    #[test]
+    fn test_extreme_contempt_saturates() {
+        let contempt = Contempt::default();
+        contempt.set_side(&Side::X, i64::MAX);
+        contempt.set_side(&Side::O, i64::MIN);
+        assert_eq!(contempt.draw(), Score::Value(i64::MIN));
+        contempt.set_side(&Side::X, i64::MIN);
+        contempt.set_side(&Side::O, i64::MAX);
+        assert_eq!(contempt.draw(), Score::Value(i64::MAX));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::core::Game;
 
-use super::Heuristic;
+use super::{Heuristic, Score};
 
 pub struct Cumulative(Vec<Box<dyn Heuristic>>);
 impl Cumulative {
@@ -10,7 +10,13 @@ impl Cumulative {
 }
 
 impl Heuristic for Cumulative {
-    fn score(&mut self, game: &Game) -> i64 {
-        self.0.iter_mut().fold(0, |acc, h| acc + h.score(game))
+    fn score(&mut self, game: &Game) -> Score {
+        self.0
+            .iter_mut()
+            .fold(Score::Value(0), |acc, h| acc + h.score(game))
+    }
+
+    fn hits(&self) -> u64 {
+        self.0.iter().map(|heuristic| heuristic.hits()).sum()
     }
 }
@@ -1,5 +1,12 @@
 use crate::core::Game;
 
+use super::Score;
+
 pub trait Heuristic {
-    fn score(&mut self, game: &Game) -> i64;
+    fn score(&mut self, game: &Game) -> Score;
+
+    /// Scores found in a cache instead of worked out, for search statistics.
+    fn hits(&self) -> u64 {
+        0
+    }
 }
@@ -5,6 +5,7 @@ pub mod cached;
 pub mod chance;
 mod columns;
 pub mod consecutive;
+pub mod contempt;
 pub mod cumulative;
 mod diagonals;
 mod direction;
@@ -13,7 +14,9 @@ mod line;
 mod lines;
 pub mod null;
 mod rows;
+pub mod score;
 pub mod termination;
+pub mod threats;
 pub mod win;
 pub mod wins;
 
@@ -22,7 +25,9 @@ pub use assurer::*;
 pub use cached::*;
 pub use chance::*;
 pub use consecutive::*;
+pub use contempt::*;
 pub use cumulative::*;
 pub use heuristic::*;
 pub use null::*;
+pub use score::*;
 pub use win::*;
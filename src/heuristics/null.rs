@@ -1,9 +1,9 @@
-use super::Heuristic;
+use super::{Heuristic, Score};
 
 pub struct Null;
 
 impl Heuristic for Null {
-    fn score(&mut self, _: &crate::core::Game) -> i64 {
-        0
+    fn score(&mut self, _: &crate::core::Game) -> Score {
+        Score::Value(0)
     }
 }
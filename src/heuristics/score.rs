@@ -0,0 +1,228 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, Neg},
+};
+
+/// Keeps estimates clear of the scores of the longest wins and losses.
+const LIMIT: i64 = i64::MAX - u16::MAX as i64 - 1;
+
+/// How good a game is, for X when a heuristic gives it, or for the side to
+/// play once a search has turned it around. Wins and losses count the moves
+/// played by the time the game ends, so the sooner win is the better one.
+#[derive(Debug, Clone, Copy)]
+pub enum Score {
+    Loss(u16),
+    Value(i64),
+    Draw,
+    Win(u16),
+}
+
+impl Score {
+    /// Better than any score a game can have.
+    pub const MAX: Score = Score::Win(0);
+    /// Worse than any score a game can have.
+    pub const MIN: Score = Score::Loss(0);
+
+    pub fn won(played: usize) -> Self {
+        Score::Win(played as u16)
+    }
+
+    pub fn lost(played: usize) -> Self {
+        Score::Loss(played as u16)
+    }
+
+    /// The moves played by the time a decided game ends, if it is decided.
+    pub fn moves(&self) -> Option<u16> {
+        match self {
+            Score::Win(moves) | Score::Loss(moves) => Some(*moves),
+            _ => None,
+        }
+    }
+
+    /// Counts the moves to the end of a decided game from a game with this
+    /// many moves played instead of from the empty board.
+    pub fn after(self, played: usize) -> Self {
+        match self {
+            Score::Win(moves) => Score::Win(moves.saturating_sub(played as u16)),
+            Score::Loss(moves) => Score::Loss(moves.saturating_sub(played as u16)),
+            score => score,
+        }
+    }
+
+    /// The least score better than this one, to search with a null window.
+    pub fn next(self) -> Self {
+        Score::from(i64::from(self).saturating_add(1))
+    }
+}
+
+impl From<Score> for i64 {
+    fn from(score: Score) -> Self {
+        match score {
+            Score::Loss(moves) => -(i64::MAX - moves as i64),
+            Score::Value(value) => value.clamp(-LIMIT, LIMIT),
+            Score::Draw => 0,
+            Score::Win(moves) => i64::MAX - moves as i64,
+        }
+    }
+}
+
+impl From<i64> for Score {
+    fn from(value: i64) -> Self {
+        if value > LIMIT {
+            Score::Win((i64::MAX - value) as u16)
+        } else if value < -LIMIT {
+            Score::Loss((i64::MAX + value.max(-i64::MAX)) as u16)
+        } else {
+            Score::Value(value)
+        }
+    }
+}
+
+impl PartialEq for Score {
+    fn eq(&self, other: &Self) -> bool {
+        i64::from(*self) == i64::from(*other)
+    }
+}
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        i64::from(*self).cmp(&i64::from(*other))
+    }
+}
+
+/// Turns a score around to the point of view of the other side.
+impl Neg for Score {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        match self {
+            Score::Loss(moves) => Score::Win(moves),
+            Score::Value(value) => Score::Value(value.saturating_neg()),
+            Score::Draw => Score::Draw,
+            Score::Win(moves) => Score::Loss(moves),
+        }
+    }
+}
+
+/// Sums estimates, while a decided game stays decided, the sooner end
+/// first, and a draw outweighs any estimate.
+impl Add for Score {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        match (self.moves(), other.moves()) {
+            (Some(mine), Some(theirs)) if theirs < mine => other,
+            (Some(_), _) => self,
+            (None, Some(_)) => other,
+            (None, None) => match (self, other) {
+                (Score::Value(mine), Score::Value(theirs)) => {
+                    Score::Value(mine.saturating_add(theirs))
+                }
+                _ => Score::Draw,
+            },
+        }
+    }
+}
+
+impl Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Score::Loss(moves) => write!(f, "loss in {}", moves),
+            Score::Value(value) => write!(f, "{}", value),
+            Score::Draw => write!(f, "draw"),
+            Score::Win(moves) => write!(f, "win in {}", moves),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_sooner_win_is_better() {
+        assert!(Score::Win(5) > Score::Win(7));
+        assert!(Score::Win(7) > Score::Value(1_000_000));
+    }
+
+    #[test]
+    fn test_later_loss_is_better() {
+        assert!(Score::Loss(7) > Score::Loss(5));
+        assert!(Score::Loss(7) < Score::Value(-1_000_000));
+    }
+
+    #[test]
+    fn test_draw_is_level() {
+        assert_eq!(Score::Draw, Score::Value(0));
+        assert_eq!(-Score::Draw, Score::Draw);
+    }
+
+    #[test]
+    fn test_negation_reverses_order() {
+        let scores = [
+            Score::MIN,
+            Score::Loss(9),
+            Score::Value(-3),
+            Score::Draw,
+            Score::Win(9),
+        ];
+        for pair in scores.windows(2) {
+            assert!(-pair[1] <= -pair[0]);
+        }
+        assert_eq!(-Score::MAX, Score::MIN);
+    }
+
+    #[test]
+    fn test_converts_through_i64() {
+        assert!(matches!(
+            Score::from(i64::from(Score::Loss(3))),
+            Score::Loss(3)
+        ));
+        assert!(matches!(
+            Score::from(i64::from(Score::Value(-42))),
+            Score::Value(-42)
+        ));
+        assert!(matches!(
+            Score::from(i64::from(Score::Win(0))),
+            Score::Win(0)
+        ));
+        assert!(matches!(
+            Score::from(i64::from(Score::Win(7))),
+            Score::Win(7)
+        ));
+    }
+
+    #[test]
+    fn test_next_is_least_better_score() {
+        assert!(matches!(Score::Value(4).next(), Score::Value(5)));
+        assert!(matches!(Score::Win(3).next(), Score::Win(2)));
+        assert!(matches!(Score::Loss(3).next(), Score::Loss(4)));
+    }
+
+    #[test]
+    fn test_decided_game_outweighs_estimates() {
+        assert!(matches!(Score::Value(8) + Score::Win(4), Score::Win(4)));
+        assert!(matches!(Score::Win(6) + Score::Loss(4), Score::Loss(4)));
+        assert!(matches!(
+            Score::Value(8) + Score::Value(-3),
+            Score::Value(5)
+        ));
+    }
+
+    #[test]
+    fn test_reports_moves_to_the_end() {
+        assert_eq!(Score::Win(12).after(9).to_string(), "win in 3");
+        assert_eq!(Score::Loss(12).after(10).to_string(), "loss in 2");
+        assert_eq!(Score::Value(-8).after(10).to_string(), "-8");
+    }
+}
@@ -0,0 +1,202 @@
+use rustc_hash::FxHashSet;
+
+use super::{line::Line, wins::wins};
+use crate::core::{Cell, Game, Position, Side};
+
+/// Every winning line that `side` is exactly `missing` stones short of, with
+/// all of its other cells playable, given as the cells still to be played.
+pub fn threats(game: &Game, side: &Side, missing: u8) -> Vec<Vec<Position>> {
+    let mut seen: FxHashSet<Line> = FxHashSet::default();
+    let mut threats = Vec::new();
+    let stone = Cell::Played(side.clone());
+    for position in &game.moves {
+        if game.get(position).as_ref() != Some(&stone) {
+            continue;
+        }
+        for line in wins(position, game.size, game.win_length) {
+            if !seen.insert(line.clone()) {
+                continue;
+            }
+            let mut empty = Vec::with_capacity(missing as usize);
+            let mut open = true;
+            for cell in &line {
+                match game.get(cell) {
+                    Some(Cell::Playable) => empty.push(cell.clone()),
+                    Some(played) if played == stone => {}
+                    _ => {
+                        open = false;
+                        break;
+                    }
+                }
+                if empty.len() > missing as usize {
+                    open = false;
+                    break;
+                }
+            }
+            if open && empty.len() == missing as usize {
+                threats.push(empty);
+            }
+        }
+    }
+    threats
+}
+
+/// Every line one longer than a win with both ends playable, like those that
+/// make an `Assurance`, whose inner cells `side` is exactly `missing` stones
+/// short of filling while the rest of them are playable.
+pub fn open(game: &Game, side: &Side, missing: u8) -> Vec<Vec<Position>> {
+    let mut seen: FxHashSet<Line> = FxHashSet::default();
+    let mut open = Vec::new();
+    let stone = Cell::Played(side.clone());
+    for position in &game.moves {
+        if game.get(position).as_ref() != Some(&stone) {
+            continue;
+        }
+        for line in wins(position, game.size, game.win_length + 1) {
+            if !seen.insert(line.clone()) {
+                continue;
+            }
+            let last = line.len() - 1;
+            if game.get(&line[0]) != Some(Cell::Playable)
+                || game.get(&line[last]) != Some(Cell::Playable)
+            {
+                continue;
+            }
+            let mut empty = 0;
+            let mut blocked = false;
+            for cell in &line[1..last] {
+                match game.get(cell) {
+                    Some(Cell::Playable) => empty += 1,
+                    Some(played) if played == stone => {}
+                    _ => {
+                        blocked = true;
+                        break;
+                    }
+                }
+            }
+            if !blocked && empty == missing {
+                open.push(line);
+            }
+        }
+    }
+    open
+}
+
+/// Whether the stone at `position` is part of a line that its side is one
+/// stone short of, with the last cell playable.
+pub fn threatens(game: &Game, position: &Position) -> bool {
+    let stone = match game.get(position) {
+        Some(Cell::Played(side)) => Cell::Played(side),
+        _ => return false,
+    };
+    wins(position, game.size, game.win_length)
+        .iter()
+        .any(|line| {
+            let mut empty = 0;
+            for cell in line {
+                match game.get(cell) {
+                    Some(Cell::Playable) => empty += 1,
+                    Some(played) if played == stone => {}
+                    _ => return false,
+                }
+            }
+            empty == 1
+        })
+}
+
+/// The cells where `side` would complete a winning line right away.
+pub fn winning_cells(game: &Game, side: &Side) -> Vec<Position> {
+    let mut cells: Vec<Position> = threats(game, side, 1).into_iter().flatten().collect();
+    cells.sort();
+    cells.dedup();
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_threats_lists_cells_missing_from_line() -> Result<()> {
+        let (_, mut game) = Game::parse("6_/_2x3_/6_/6_/6_/6_ o")?;
+        game.set_win_length(4);
+        let mut actual = threats(&game, &Side::X, 2);
+        actual.iter_mut().for_each(|cells| cells.sort());
+        actual.sort();
+        let expected = vec![
+            vec![Position(1, 0), Position(1, 3)],
+            vec![Position(1, 3), Position(1, 4)],
+        ];
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_threats_ignores_blocked_lines() -> Result<()> {
+        let (_, mut game) = Game::parse("o2x.2_/6_/6_/6_/6_/6_ o")?;
+        game.set_win_length(4);
+        assert_eq!(threats(&game, &Side::X, 2), Vec::<Vec<Position>>::new());
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_finds_three_with_both_ends_playable() -> Result<()> {
+        let (_, mut game) = Game::parse("7_/_2x_x2_/7_/7_/7_/7_/7_ o")?;
+        game.set_win_length(5);
+        let expected = vec![vec![
+            Position(1, 5),
+            Position(1, 4),
+            Position(1, 3),
+            Position(1, 2),
+            Position(1, 1),
+            Position(1, 0),
+        ]];
+        assert_eq!(open(&game, &Side::X, 1), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_ignores_lines_closed_at_an_end() -> Result<()> {
+        let (_, mut game) = Game::parse("7_/o2x_x2_/7_/7_/7_/7_/7_ o")?;
+        game.set_win_length(5);
+        assert_eq!(open(&game, &Side::X, 1), Vec::<Vec<Position>>::new());
+        Ok(())
+    }
+
+    #[test]
+    fn test_threatens_when_one_short() -> Result<()> {
+        let (_, mut game) = Game::parse("5_/_3x_/5_/5_/5_ o")?;
+        game.set_win_length(4);
+        assert!(threatens(&game, &Position(1, 2)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_does_not_threaten_when_blocked() -> Result<()> {
+        let (_, mut game) = Game::parse("5_/o3x_/5_/5_/5_ o")?;
+        game.set_win_length(4);
+        assert!(threatens(&game, &Position(1, 2)));
+        game.set_win_length(5);
+        assert!(!threatens(&game, &Position(1, 2)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_winning_cells_finds_both_ends_of_open_line() -> Result<()> {
+        let (_, mut game) = Game::parse("5_/_3x_/5_/5_/5_ o")?;
+        game.set_win_length(4);
+        let expected = vec![Position(1, 0), Position(1, 4)];
+        assert_eq!(winning_cells(&game, &Side::X), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_winning_cells_is_empty_for_other_side() -> Result<()> {
+        let (_, mut game) = Game::parse("5_/_3x_/5_/5_/5_ o")?;
+        game.set_win_length(4);
+        assert_eq!(winning_cells(&game, &Side::O), vec![]);
+        Ok(())
+    }
+}
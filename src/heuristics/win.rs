@@ -1,29 +1,42 @@
+use std::sync::Arc;
+
 use crate::core::{Game, Side};
 
-use super::{termination::Termination, Heuristic};
+use super::{termination::Termination, Contempt, Heuristic, Score};
 
-pub struct Win(Box<dyn Heuristic>);
+pub struct Win(Box<dyn Heuristic>, Arc<Contempt>);
 
 impl Win {
     pub fn new(heuristic: Box<dyn Heuristic>) -> Self {
-        Win(heuristic)
+        Win(heuristic, Arc::default())
+    }
+
+    /// Scores draws by the contempt given instead of as nothing to either
+    /// side.
+    pub fn with_contempt(heuristic: Box<dyn Heuristic>, contempt: Arc<Contempt>) -> Self {
+        Win(heuristic, contempt)
     }
 }
 
 impl Heuristic for Win {
-    fn score(&mut self, game: &Game) -> i64 {
+    fn score(&mut self, game: &Game) -> Score {
         match Termination::of(game) {
-            Some(Termination::Drawn) => 0,
-            Some(Termination::Won(Side::X)) => i64::MAX - (game.moves.len() as i64),
-            Some(Termination::Won(Side::O)) => i64::MIN + (game.moves.len() as i64),
+            Some(Termination::Drawn) => self.1.draw(),
+            Some(Termination::Won(Side::X)) => Score::won(game.moves.len()),
+            Some(Termination::Won(Side::O)) => Score::lost(game.moves.len()),
+            None if !game.winnable() => self.1.draw(),
             None => self.0.score(game),
         }
     }
+
+    fn hits(&self) -> u64 {
+        self.0.hits()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::heuristics::Null;
+    use crate::heuristics::{Chance, Null};
 
     use super::*;
     use anyhow::Result;
@@ -36,7 +49,27 @@ mod tests {
         )?;
         game.set_win_length(5);
         let mut heuristic = Win::new(Box::new(Null));
-        assert_eq!(heuristic.score(&game), 0);
+        assert_eq!(heuristic.score(&game), Score::Value(0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_win_scores_blocked_board_as_draw() -> Result<()> {
+        let (_, game) = Game::parse("x_o/_._/o_x x")?;
+        let mut heuristic = Win::new(Box::new(Chance));
+        assert_eq!(heuristic.score(&game), Score::Draw);
+        Ok(())
+    }
+
+    #[test]
+    fn test_win_shifts_draws_by_contempt() -> Result<()> {
+        let (_, drawn) = Game::parse("xox/xoo/oxx o")?;
+        let (_, blocked) = Game::parse("x_o/_._/o_x x")?;
+        let contempt = Arc::new(Contempt::default());
+        contempt.set_side(&Side::X, 40);
+        let mut heuristic = Win::with_contempt(Box::new(Chance), contempt);
+        assert_eq!(heuristic.score(&drawn), Score::Value(-40));
+        assert_eq!(heuristic.score(&blocked), Score::Value(-40));
         Ok(())
     }
 }
@@ -6,6 +6,6 @@ pub struct All;
 
 impl Looker for All {
     fn moves(&mut self, game: &Game) -> Vec<Position> {
-        Vec::from_iter(game.playable.iter().map(|x| x.clone()))
+        Vec::from_iter(game.playable.iter().cloned())
     }
 }
@@ -10,8 +10,8 @@ fn nearby(position: Position, distance: u8, size: u8) -> Vec<Position> {
     let mut nearby = Vec::with_capacity((distance as usize * 2 + 1).pow(2));
     for x in -(distance as isize)..=distance as isize {
         for y in -(distance as isize)..=distance as isize {
-            let i = (position.0 as isize + x) as isize;
-            let j = (position.1 as isize + y) as isize;
+            let i = position.0 as isize + x;
+            let j = position.1 as isize + y;
             if i >= 0 && i < size as isize && j >= 0 && j < size as isize {
                 nearby.push(Position(i as u8, j as u8));
             }
@@ -22,7 +22,7 @@ fn nearby(position: Position, distance: u8, size: u8) -> Vec<Position> {
 
 #[inline(always)]
 fn near_played(
-    played: &Vec<Position>,
+    played: &[Position],
     playable: &FxHashSet<Position>,
     distance: u8,
     size: u8,
@@ -1,15 +1,14 @@
-use rand::rngs::ThreadRng;
 use rand::seq::SliceRandom;
 
-use crate::core::{Game, Position};
+use crate::core::{Game, Position, Seeded};
 
 use super::looker::Looker;
 
-pub struct Shuffler(Box<dyn Looker>, ThreadRng);
+pub struct Shuffler(Box<dyn Looker>, Seeded);
 
 impl Shuffler {
-    pub fn new(looker: Box<dyn Looker>, thread_rng: ThreadRng) -> Self {
-        Self(looker, thread_rng)
+    pub fn new(looker: Box<dyn Looker>, rng: Seeded) -> Self {
+        Self(looker, rng)
     }
 }
 
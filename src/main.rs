@@ -3,33 +3,150 @@ pub mod hashers;
 pub mod heuristics;
 pub mod lookers;
 pub mod players;
+pub mod solvers;
 
 use std::{
+    env,
     error::Error,
+    fs,
     io::{self, BufRead, Write},
+    path::Path,
     process,
+    sync::Arc,
+    thread,
 };
 
-use crate::core::{zobrist, Command};
+use crate::core::{
+    parse_book, parse_generate, set_seed, zobrist, BookOptions, Command, Position, Seeded, Setting,
+    Side,
+};
 use hashers::Transposer;
-use heuristics::{Assurer, Cached, Chance, Win};
+use heuristics::{termination::Termination, Assurer, Cached, Chance, Contempt, Win};
 use lookers::{Nearby, Shuffler};
-use players::{Player, Thinker};
-use rand::thread_rng;
+use players::{Book, Opener, Player, Records, Skill, Thinker};
+use solvers::Database;
 
 const URL: &str = "https://github.com/artfuldev/rustep";
 
+fn set_contempt(contempt: &Contempt, side: Option<Side>, value: i64) {
+    match side {
+        Some(side) => contempt.set_side(&side, value),
+        None => contempt.set_engine(value),
+    }
+}
+
+/// Adds the games imported and played by the player to the records and
+/// rebuilds the book from them.
+fn build_book(options: BookOptions, player: &mut dyn Player) -> Result<(), Box<dyn Error>> {
+    let start = &options.game;
+    let plies = options.plies as usize;
+    let mut records = match Path::new(&options.records).exists() {
+        true => Records::load(&options.records)?,
+        false => Records::new(start.size, start.win_length),
+    };
+    let mut recorded = 0;
+    if let Some(path) = &options.import {
+        for (index, line) in fs::read_to_string(path)?.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let moves: Option<Vec<Position>> = line
+                .split_whitespace()
+                .map(|position| match Position::parse(position) {
+                    Ok(("", position)) => Some(position),
+                    _ => None,
+                })
+                .collect();
+            let result = match moves {
+                Some(moves) => records.record(start, &moves, plies),
+                None => Err(anyhow::anyhow!("invalid moves")),
+            };
+            match result {
+                Ok(()) => recorded += 1,
+                Err(error) => eprintln!("skipping game {}: {}", index + 1, error),
+            }
+        }
+    }
+    for _ in 0..options.self_play {
+        let mut game = start.clone();
+        while Termination::of(&game).is_none() && !game.playable.is_empty() {
+            let position = player.best(&mut game, None, &options.limits)?;
+            game.play(&position);
+        }
+        records.record(start, &game.moves[start.moves.len()..], plies)?;
+        recorded += 1;
+    }
+    records.save(&options.records)?;
+    let book = records.book(options.min_games, options.min_score);
+    book.save(&options.book)?;
+    println!(
+        "recorded {} games, {} positions in {}",
+        recorded,
+        book.len(),
+        options.book
+    );
+    Ok(())
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
     let name = env!("CARGO_PKG_NAME");
     let version = env!("CARGO_PKG_VERSION");
     let author = env!("CARGO_PKG_AUTHORS");
-    let mut player = Thinker::new(
-        Box::new(Cached::new(
-            Box::new(Win::new(Box::new(Assurer::new(Box::new(Chance))))),
-            Box::new(Transposer),
-        )),
-        Box::new(Shuffler::new(Box::new(Nearby::new(2)), thread_rng())),
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let contempt = Arc::new(Contempt::default());
+    let shared = contempt.clone();
+    let mut thinker = Thinker::parallel(
+        // Draws are scored outside the cache, which outlives any change of
+        // contempt.
+        Arc::new(move || {
+            (
+                Box::new(Win::with_contempt(
+                    Box::new(Cached::new(
+                        Box::new(Assurer::new(Box::new(Chance))),
+                        Box::new(Transposer),
+                    )),
+                    shared.clone(),
+                )),
+                Box::new(Shuffler::new(Box::new(Nearby::new(2)), Seeded::new())),
+            )
+        }),
+        2,
+        threads,
     );
+    thinker.set_contempt(contempt.clone());
+    let arguments = env::args().skip(1).collect::<Vec<_>>().join(" ");
+    if let Ok((_, (path, game))) = parse_generate(&arguments) {
+        let database = Database::generate(&game);
+        database.save(&path)?;
+        println!("generated {} positions in {}", database.len(), path);
+        return Ok(());
+    }
+    if let Ok(("", options)) = parse_book(&arguments) {
+        return build_book(options, &mut thinker);
+    }
+    let (unknown, settings) = Setting::parse_arguments(&arguments)
+        .map_err(|error| format!("invalid arguments: {}", error))?;
+    if !unknown.trim().is_empty() {
+        return Err(format!("unknown arguments: {}", unknown.trim()).into());
+    }
+    let mut ponder = false;
+    let mut book = None;
+    for setting in settings {
+        match setting {
+            Setting::Threads(threads) => thinker.set_threads(threads as usize),
+            Setting::Ponder(on) => ponder = on,
+            Setting::MultiPv(count) => thinker.set_multi_pv(count as usize),
+            Setting::Solutions(path) => thinker.set_database(Arc::new(Database::load(path)?)),
+            Setting::Book(path) => book = Some(Book::load(path)?),
+            Setting::Seed(seed) => set_seed(seed),
+            Setting::Skill(level) => thinker.set_skill(Skill::new(level)),
+            Setting::Contempt(side, value) => set_contempt(&contempt, side, value),
+        }
+    }
+    let mut player: Box<dyn Player> = match book {
+        Some(book) => Box::new(Opener::new(book, Box::new(thinker), Seeded::new())),
+        None => Box::new(thinker),
+    };
     loop {
         let mut buffer = String::new();
         let mut stdin = io::stdin().lock();
@@ -52,18 +169,39 @@ fn main() -> Result<(), Box<dyn Error>> {
                     writeln!(stdout, "identify ok")?;
                     stdout.flush()?;
                 }
-                Command::Move(mut game, time) => match player.best(&mut game, time) {
-                    Ok(position) => {
-                        let mut stdout = io::stdout().lock();
-                        writeln!(stdout, "best {}", position)?;
+                Command::Move(mut game, time, limits) => {
+                    match player.best(&mut game, time, &limits) {
+                        Ok(position) => {
+                            let mut stdout = io::stdout().lock();
+                            writeln!(stdout, "best {}", position)?;
+                            stdout.flush()?;
+                            if ponder {
+                                player.ponder(&game);
+                            }
+                        }
+                        Err(error) => {
+                            let mut stderr = io::stderr().lock();
+                            writeln!(stderr, "move error: {}", error)?;
+                            stderr.flush()?;
+                        }
                     }
-                    Err(error) => {
+                }
+                Command::Option(setting) => match setting {
+                    Setting::Seed(seed) => set_seed(seed),
+                    Setting::Ponder(on) => ponder = on,
+                    Setting::Contempt(side, value) => set_contempt(&contempt, side, value),
+                    other => {
                         let mut stderr = io::stderr().lock();
-                        writeln!(stderr, "move error: {}", error)?;
+                        writeln!(
+                            stderr,
+                            "option only applies on the command line: {:?}",
+                            other
+                        )?;
                         stderr.flush()?;
                     }
                 },
                 Command::Quit => {
+                    drop(player);
                     process::exit(0);
                 }
             },
@@ -0,0 +1,282 @@
+use std::{fmt::Display, fs, path::Path};
+
+use anyhow::{anyhow, bail, Result};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{hex_digit1, multispace0, space1, u32, u8},
+    combinator::map_res,
+    multi::{many0, many1},
+    sequence::{pair, preceded, terminated, tuple},
+    IResult,
+};
+use rand::Rng;
+use rustc_hash::FxHashMap;
+
+use crate::{
+    core::{Game, Position},
+    hashers::{inverse, Transposer, SYMMETRIES},
+};
+
+/// Positions by canonical hash, each with moves as played on the canonical
+/// board and what is known about each of them.
+pub(super) type Entries<T> = FxHashMap<u64, Vec<(Position, T)>>;
+
+/// Moves worth playing in known positions, with a weight for how often to
+/// play each. Positions are kept by canonical hash and moves as they would be
+/// played on the canonical board, so that one entry serves every turn and
+/// flip of a position.
+///
+/// Books are written as text, a `book <size> <win-length>` line followed by a
+/// line per position with its hash in hex and its moves and weights, like
+/// `5f1c07e2a9d3b804 h8 12 i9 3`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Book {
+    size: u8,
+    win_length: u8,
+    entries: Entries<u32>,
+}
+
+pub(super) fn parse_hash(input: &str) -> IResult<&str, u64> {
+    map_res(hex_digit1, |hex| u64::from_str_radix(hex, 16))(input)
+}
+
+/// Parses a `<kind> <size> <win-length>` line followed by a line per position
+/// with its hash and its moves, each with what `value` parses after it.
+pub(super) fn parse_entries<'a, T>(
+    kind: &'static str,
+    value: fn(&'a str) -> IResult<&'a str, T>,
+    input: &'a str,
+) -> IResult<&'a str, (u8, u8, Entries<T>)> {
+    let (remaining, ((size, win_length), entries)) = pair(
+        preceded(
+            tuple((multispace0, tag(kind), space1)),
+            tuple((terminated(u8, space1), u8)),
+        ),
+        many0(preceded(
+            multispace0,
+            pair(
+                parse_hash,
+                many1(preceded(
+                    space1,
+                    pair(Position::parse, preceded(space1, value)),
+                )),
+            ),
+        )),
+    )(input)?;
+    let (remaining, _) = multispace0(remaining)?;
+    Ok((remaining, (size, win_length, entries.into_iter().collect())))
+}
+
+/// Writes entries the way `parse_entries` reads them, by hash.
+pub(super) fn write_entries<T: Display>(
+    f: &mut std::fmt::Formatter<'_>,
+    kind: &str,
+    size: u8,
+    win_length: u8,
+    entries: &Entries<T>,
+) -> std::fmt::Result {
+    writeln!(f, "{} {} {}", kind, size, win_length)?;
+    let mut hashes: Vec<&u64> = entries.keys().collect();
+    hashes.sort();
+    for hash in hashes {
+        write!(f, "{:016x}", hash)?;
+        for (position, value) in &entries[hash] {
+            write!(f, " {} {}", position, value)?;
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+/// Reads a whole text file of the kind given with its parser.
+pub(super) fn load_text<P: AsRef<Path>, T>(
+    path: P,
+    kind: &str,
+    parse: fn(&str) -> IResult<&str, T>,
+) -> Result<T> {
+    let text = fs::read_to_string(path)?;
+    let (remaining, parsed) =
+        parse(&text).map_err(|error| anyhow!("invalid {}: {}", kind, error))?;
+    if !remaining.is_empty() {
+        bail!(
+            "invalid {} entry: {}",
+            kind,
+            remaining.lines().next().unwrap_or("")
+        );
+    }
+    Ok(parsed)
+}
+
+pub(super) fn save_text<P: AsRef<Path>, T: Display>(path: P, text: &T) -> Result<()> {
+    fs::write(path, text.to_string())?;
+    Ok(())
+}
+
+impl Book {
+    pub fn new(size: u8, win_length: u8) -> Self {
+        Self {
+            size,
+            win_length,
+            entries: FxHashMap::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn covers(&self, game: &Game) -> bool {
+        (game.size, game.win_length) == (self.size, self.win_length)
+    }
+
+    /// Adds weight to a move in a position, adding either if it is new.
+    pub fn add(&mut self, game: &Game, position: &Position, weight: u32) {
+        if !self.covers(game) {
+            return;
+        }
+        let (hash, symmetry) = Transposer.canonical(game);
+        let position = SYMMETRIES[symmetry](position, game.size);
+        let moves = self.entries.entry(hash).or_default();
+        match moves.iter_mut().find(|(known, _)| *known == position) {
+            Some((_, known)) => *known = known.saturating_add(weight),
+            None => moves.push((position, weight)),
+        }
+    }
+
+    /// Adds a move to the book as it is played on the canonical board.
+    pub(crate) fn insert(&mut self, hash: u64, position: Position, weight: u32) {
+        self.entries
+            .entry(hash)
+            .or_default()
+            .push((position, weight));
+    }
+
+    /// The moves in the book for the game, as played on its own board.
+    pub fn moves(&self, game: &Game) -> Vec<(Position, u32)> {
+        if !self.covers(game) {
+            return vec![];
+        }
+        let (hash, symmetry) = Transposer.canonical(game);
+        let back = SYMMETRIES[inverse(symmetry)];
+        match self.entries.get(&hash) {
+            Some(moves) => moves
+                .iter()
+                .map(|(position, weight)| (back(position, game.size), *weight))
+                .filter(|(position, weight)| *weight > 0 && game.playable.contains(position))
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Picks one of the moves in the book for the game, each as often as its
+    /// share of the total weight.
+    pub fn pick<R: Rng>(&self, game: &Game, rng: &mut R) -> Option<Position> {
+        let moves = self.moves(game);
+        let total: u64 = moves.iter().map(|(_, weight)| *weight as u64).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut remaining = rng.gen_range(0..total);
+        for (position, weight) in moves {
+            if remaining < weight as u64 {
+                return Some(position);
+            }
+            remaining -= weight as u64;
+        }
+        None
+    }
+
+    pub fn parse(input: &str) -> IResult<&str, Book> {
+        let (remaining, (size, win_length, entries)) = parse_entries("book", u32, input)?;
+        let book = Book {
+            size,
+            win_length,
+            entries,
+        };
+        Ok((remaining, book))
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        load_text(path, "book", Book::parse)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        save_text(path, self)
+    }
+}
+
+impl Display for Book {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_entries(f, "book", self.size, self.win_length, &self.entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Seeded;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_serves_every_turn_of_a_position() -> Result<()> {
+        let (_, game) = Game::parse("x3_/4_/4_/_o2_ x")?;
+        let (_, turned) = Game::parse("_o2_/4_/4_/x3_ x")?;
+        let mut book = Book::new(4, 4);
+        book.add(&game, &Position(1, 2), 5);
+        assert_eq!(book.moves(&turned), vec![(Position(2, 2), 5)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_adds_up_weights_of_the_same_move() -> Result<()> {
+        let (_, game) = Game::parse("3_/3_/3_ x")?;
+        let mut book = Book::new(3, 3);
+        book.add(&game, &Position(1, 1), 2);
+        book.add(&game, &Position(1, 1), 3);
+        assert_eq!(book.moves(&game), vec![(Position(1, 1), 5)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_picks_only_weighted_moves() -> Result<()> {
+        let (_, game) = Game::parse("3_/3_/3_ x")?;
+        let mut book = Book::new(3, 3);
+        book.add(&game, &Position(0, 0), 0);
+        book.add(&game, &Position(1, 1), 1);
+        let mut rng = Seeded::private(1);
+        for _ in 0..20 {
+            assert_eq!(book.pick(&game, &mut rng), Some(Position(1, 1)));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_has_nothing_for_other_rules() -> Result<()> {
+        let (_, mut game) = Game::parse("3_/3_/3_ x")?;
+        let mut book = Book::new(3, 3);
+        book.add(&game, &Position(1, 1), 1);
+        game.set_win_length(2);
+        assert_eq!(book.pick(&game, &mut Seeded::private(2)), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reads_what_it_writes() -> Result<()> {
+        let (_, mut game) = Game::parse("5_/5_/5_/5_/5_ x")?;
+        game.set_win_length(4);
+        let mut book = Book::new(5, 4);
+        book.add(&game, &Position(2, 2), 10);
+        game.play(&Position(2, 2));
+        book.add(&game, &Position(1, 1), 4);
+        book.add(&game, &Position(1, 2), 1);
+        let written = book.to_string();
+        let (remaining, read) = Book::parse(&written).expect("parsed");
+        assert_eq!(remaining, "");
+        assert_eq!(read, book);
+        Ok(())
+    }
+}
@@ -0,0 +1,322 @@
+use std::time::Instant;
+
+use anyhow::{bail, Result};
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    core::{Game, Position, SearchLimits, Seeded, Side, Time},
+    heuristics::{termination::Termination, Assurance, Heuristic, Score},
+    lookers::{All, Looker},
+};
+
+use super::Player;
+
+/// How strongly UCT favours children that have been visited less.
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+/// Candidates a guided playout samples before letting the heuristic choose.
+const SAMPLES: usize = 4;
+
+#[inline(always)]
+fn termination(game: &Game) -> Option<Termination> {
+    Termination::of(game)
+        .or_else(|| Assurance::of(game).map(|Assurance(side, _)| Termination::Won(side)))
+}
+
+struct Node {
+    position: Option<Position>,
+    parent: usize,
+    children: Vec<usize>,
+    untried: Vec<Position>,
+    visits: u32,
+    reward: f64,
+}
+
+impl Node {
+    fn uct(&self, parent_visits: u32) -> f64 {
+        let visits = self.visits as f64;
+        self.reward / visits + EXPLORATION * ((parent_visits as f64).ln() / visits).sqrt()
+    }
+}
+
+/// Plays the most visited move of a Monte Carlo tree search that selects with
+/// UCT and scores leaves by playing the game out, at random or guided by a
+/// heuristic.
+pub struct Explorer {
+    looker: Box<dyn Looker>,
+    heuristic: Option<Box<dyn Heuristic>>,
+    rng: Seeded,
+    iterations: usize,
+    nodes: Vec<Node>,
+}
+
+impl Explorer {
+    pub fn new(looker: Box<dyn Looker>, rng: Seeded, iterations: usize) -> Self {
+        Self {
+            looker,
+            heuristic: None,
+            rng,
+            iterations,
+            nodes: vec![],
+        }
+    }
+
+    pub fn guided(
+        looker: Box<dyn Looker>,
+        heuristic: Box<dyn Heuristic>,
+        rng: Seeded,
+        iterations: usize,
+    ) -> Self {
+        Self {
+            heuristic: Some(heuristic),
+            ..Self::new(looker, rng, iterations)
+        }
+    }
+
+    /// The moves of the looker, or every cell when it has nothing to suggest
+    /// on a board with cells left, like `Nearby` with no stone close enough.
+    fn moves(&mut self, game: &Game) -> Vec<Position> {
+        match self.looker.moves(game) {
+            moves if moves.is_empty() => All.moves(game),
+            moves => moves,
+        }
+    }
+
+    fn untried(&mut self, game: &Game) -> Vec<Position> {
+        match termination(game) {
+            Some(_) => vec![],
+            None => self.moves(game),
+        }
+    }
+
+    fn select(&self, index: usize) -> usize {
+        let visits = self.nodes[index].visits;
+        self.nodes[index]
+            .children
+            .iter()
+            .copied()
+            .max_by(|&a, &b| {
+                self.nodes[a]
+                    .uct(visits)
+                    .total_cmp(&self.nodes[b].uct(visits))
+            })
+            .expect("expanded")
+    }
+
+    fn pick(&mut self, game: &mut Game, moves: &[Position]) -> Position {
+        let heuristic = match self.heuristic.as_mut() {
+            Some(heuristic) => heuristic,
+            None => return moves[self.rng.gen_range(0..moves.len())].clone(),
+        };
+        let side = game.side_to_play.clone();
+        let mut best = (Score::MIN, None);
+        for position in moves.choose_multiple(&mut self.rng, SAMPLES) {
+            game.play(position);
+            let score = match side {
+                Side::X => heuristic.score(game),
+                Side::O => -heuristic.score(game),
+            };
+            game.undo();
+            if best.1.is_none() || score > best.0 {
+                best = (score, Some(position.clone()));
+            }
+        }
+        best.1.expect("sampled")
+    }
+
+    /// Plays random moves until the game ends and returns the winner, if any.
+    fn playout(&mut self, game: &mut Game) -> Option<Side> {
+        let mut played = 0;
+        let winner = loop {
+            match termination(game) {
+                Some(Termination::Won(side)) => break Some(side),
+                Some(Termination::Drawn) => break None,
+                None => {}
+            }
+            let moves = self.moves(game);
+            if moves.is_empty() {
+                break None;
+            }
+            let position = self.pick(game, &moves);
+            game.play(&position);
+            played += 1;
+        };
+        for _ in 0..played {
+            game.undo();
+        }
+        winner
+    }
+
+    fn iterate(&mut self, game: &mut Game) {
+        let mut index = 0;
+        let mut depth = 0;
+        while self.nodes[index].untried.is_empty() && !self.nodes[index].children.is_empty() {
+            index = self.select(index);
+            game.play(self.nodes[index].position.as_ref().expect("child"));
+            depth += 1;
+        }
+        let count = self.nodes[index].untried.len();
+        if count > 0 {
+            let position = self.nodes[index]
+                .untried
+                .swap_remove(self.rng.gen_range(0..count));
+            game.play(&position);
+            depth += 1;
+            let child = Node {
+                position: Some(position),
+                parent: index,
+                children: vec![],
+                untried: self.untried(game),
+                visits: 0,
+                reward: 0.0,
+            };
+            let child_index = self.nodes.len();
+            self.nodes[index].children.push(child_index);
+            self.nodes.push(child);
+            index = child_index;
+        }
+        let winner = self.playout(game);
+        // Every node is rewarded from the point of view of the side that
+        // made the move leading to it.
+        let mut mover = game.side_to_play.other();
+        loop {
+            let node = &mut self.nodes[index];
+            node.visits += 1;
+            node.reward += match &winner {
+                Some(side) if *side == mover => 1.0,
+                Some(_) => 0.0,
+                None => 0.5,
+            };
+            if index == 0 {
+                break;
+            }
+            index = node.parent;
+            mover = mover.other();
+        }
+        for _ in 0..depth {
+            game.undo();
+        }
+    }
+}
+
+impl Player for Explorer {
+    fn best(
+        &mut self,
+        game: &mut Game,
+        time: Option<Time>,
+        limits: &SearchLimits,
+    ) -> Result<Position> {
+        let deadline = limits.deadline(Instant::now(), time.map(|time| time.budget(game)));
+        let most = limits
+            .nodes
+            .map_or(self.iterations, |nodes| self.iterations.min(nodes as usize));
+        self.nodes.clear();
+        let untried = self.untried(game);
+        if untried.is_empty() {
+            bail!("No moves left!");
+        }
+        self.nodes.push(Node {
+            position: None,
+            parent: 0,
+            children: vec![],
+            untried,
+            visits: 0,
+            reward: 0.0,
+        });
+        let mut iterations = 0;
+        while iterations < most && deadline.is_none_or(|deadline| Instant::now() < deadline) {
+            self.iterate(game);
+            iterations += 1;
+        }
+        let best = self.nodes[0]
+            .children
+            .iter()
+            .map(|&child| &self.nodes[child])
+            .max_by_key(|node| node.visits);
+        match best {
+            Some(node) => {
+                let position = node.position.clone().expect("child");
+                println!(
+                    "info iterations {} pv {:?} rate {:.3}",
+                    iterations,
+                    vec![position.clone()],
+                    node.reward / node.visits as f64
+                );
+                Ok(position)
+            }
+            None => bail!("No moves found!"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::Duration,
+        heuristics::Chance,
+        lookers::{All, Nearby},
+    };
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_explorer_takes_immediate_win() -> Result<()> {
+        let (_, mut game) = Game::parse("xx_/oo_/3_ x")?;
+        let mut explorer = Explorer::new(Box::new(All), Seeded::private(1), 2_000);
+        assert_eq!(
+            explorer.best(&mut game, None, &SearchLimits::default())?,
+            Position(0, 2)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_explorer_blocks_immediate_loss() -> Result<()> {
+        let (_, mut game) = Game::parse("xx_/_o_/3_ o")?;
+        let mut explorer = Explorer::new(Box::new(All), Seeded::private(2), 5_000);
+        assert_eq!(
+            explorer.best(&mut game, None, &SearchLimits::default())?,
+            Position(0, 2)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_guided_explorer_blocks_immediate_loss() -> Result<()> {
+        let (_, mut game) = Game::parse("xx_/_o_/3_ o")?;
+        let mut explorer =
+            Explorer::guided(Box::new(All), Box::new(Chance), Seeded::private(3), 2_000);
+        assert_eq!(
+            explorer.best(&mut game, None, &SearchLimits::default())?,
+            Position(0, 2)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_explorer_searches_every_cell_when_looker_has_none() -> Result<()> {
+        let (_, mut game) = Game::parse("5_/5_/2_.2_/5_/5_ x")?;
+        game.set_win_length(3);
+        let mut explorer = Explorer::new(Box::new(Nearby::new(1)), Seeded::private(5), 500);
+        let position = explorer.best(&mut game, None, &SearchLimits::default())?;
+        assert!(game.playable.contains(&position));
+        Ok(())
+    }
+
+    #[test]
+    fn test_explorer_plays_around_holes_within_time() -> Result<()> {
+        let (_, mut game) = Game::parse("7_/_.3_._/3_x3_/2_.o.2_/7_/_.3_._/7_ x")?;
+        game.set_win_length(4);
+        let mut explorer = Explorer::new(Box::new(Nearby::new(1)), Seeded::private(4), usize::MAX);
+        let start = Instant::now();
+        let position = explorer.best(
+            &mut game,
+            Some(Time::PerMove(Duration::Milliseconds(200))),
+            &SearchLimits::default(),
+        )?;
+        assert!(start.elapsed() < std::time::Duration::from_millis(200));
+        assert!(game.playable.contains(&position));
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use crate::core::{Position, Side};
+
+/// How often a move by a side caused a beta cut-off, weighted by the depth
+/// of the search that it cut short.
+#[derive(Default)]
+pub struct History {
+    size: u8,
+    scores: Vec<u64>,
+}
+
+impl History {
+    #[inline(always)]
+    fn index(&self, Position(row, column): &Position, side: &Side) -> usize {
+        let size = self.size as usize;
+        let offset = match side {
+            Side::X => 0,
+            Side::O => size * size,
+        };
+        offset + *row as usize * size + *column as usize
+    }
+
+    /// Halves every score so that a new search favours what it learns itself.
+    pub fn age(&mut self, size: u8) {
+        if self.size != size {
+            self.size = size;
+            self.scores = vec![0; (size as usize).pow(2) * 2];
+        }
+        for score in self.scores.iter_mut() {
+            *score /= 2;
+        }
+    }
+
+    pub fn get(&self, position: &Position, side: &Side) -> u64 {
+        match self.scores.get(self.index(position, side)) {
+            Some(&score) => score,
+            None => 0,
+        }
+    }
+
+    pub fn reward(&mut self, position: &Position, side: &Side, depth: u8) {
+        let index = self.index(position, side);
+        if let Some(score) = self.scores.get_mut(index) {
+            *score = score.saturating_add((depth as u64).pow(2));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_reward_is_per_side() {
+        let mut history = History::default();
+        history.age(3);
+        history.reward(&Position(1, 2), &Side::X, 3);
+        assert_eq!(history.get(&Position(1, 2), &Side::X), 9);
+        assert_eq!(history.get(&Position(1, 2), &Side::O), 0);
+    }
+
+    #[test]
+    fn test_age_halves_scores() {
+        let mut history = History::default();
+        history.age(3);
+        history.reward(&Position(0, 0), &Side::O, 4);
+        history.age(3);
+        assert_eq!(history.get(&Position(0, 0), &Side::O), 8);
+    }
+
+    #[test]
+    fn test_age_resets_for_new_size() {
+        let mut history = History::default();
+        history.age(3);
+        history.reward(&Position(0, 0), &Side::O, 4);
+        history.age(5);
+        assert_eq!(history.get(&Position(0, 0), &Side::O), 0);
+    }
+}
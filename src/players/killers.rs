@@ -0,0 +1,61 @@
+use crate::core::Position;
+
+/// Two moves per ply that recently caused a beta cut-off, most recent first.
+#[derive(Default)]
+pub struct Killers(Vec<[Option<Position>; 2]>);
+
+impl Killers {
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    pub fn get(&self, ply: usize) -> &[Option<Position>] {
+        match self.0.get(ply) {
+            Some(slots) => slots,
+            None => &[],
+        }
+    }
+
+    pub fn store(&mut self, ply: usize, position: &Position) {
+        if self.0.len() <= ply {
+            self.0.resize(ply + 1, [None, None]);
+        }
+        let slots = &mut self.0[ply];
+        if slots[0].as_ref() != Some(position) {
+            slots[1] = slots[0].take();
+            slots[0] = Some(position.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_store_keeps_two_most_recent() {
+        let mut killers = Killers::default();
+        killers.store(2, &Position(0, 0));
+        killers.store(2, &Position(1, 1));
+        killers.store(2, &Position(2, 2));
+        assert_eq!(
+            killers.get(2),
+            &[Some(Position(2, 2)), Some(Position(1, 1))]
+        );
+    }
+
+    #[test]
+    fn test_store_does_not_duplicate() {
+        let mut killers = Killers::default();
+        killers.store(0, &Position(0, 0));
+        killers.store(0, &Position(0, 0));
+        assert_eq!(killers.get(0), &[Some(Position(0, 0)), None]);
+    }
+
+    #[test]
+    fn test_get_returns_nothing_for_unseen_ply() {
+        let killers = Killers::default();
+        assert!(killers.get(5).is_empty());
+    }
+}
@@ -1,7 +1,29 @@
+mod book;
+mod explorer;
+mod history;
+mod killers;
+mod opener;
+mod oracle;
 mod player;
+mod prover;
 mod random;
+mod records;
+mod skill;
+mod stats;
+mod table;
 mod thinker;
 
+pub use book::Book;
+pub use explorer::Explorer;
+pub use history::History;
+pub use killers::Killers;
+pub use opener::Opener;
+pub use oracle::Oracle;
 pub use player::Player;
+pub use prover::Prover;
 pub use random::Random;
-pub use thinker::Thinker;
+pub use records::{Records, Tally};
+pub use skill::{Skill, MAX_SKILL};
+pub use stats::Stats;
+pub use table::{Entry, Table};
+pub use thinker::{SymmetryPruning, Thinker};
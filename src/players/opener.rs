@@ -0,0 +1,88 @@
+use anyhow::Result;
+
+use crate::core::{Game, Position, SearchLimits, Seeded, Time};
+
+use super::{Book, Player};
+
+/// Plays from the book while the game is in it and leaves the rest to the
+/// player it wraps.
+pub struct Opener {
+    book: Book,
+    inner: Box<dyn Player>,
+    rng: Seeded,
+    booked: bool,
+}
+
+impl Opener {
+    pub fn new(book: Book, inner: Box<dyn Player>, rng: Seeded) -> Self {
+        Self {
+            book,
+            inner,
+            rng,
+            booked: false,
+        }
+    }
+}
+
+impl Player for Opener {
+    fn best(
+        &mut self,
+        game: &mut Game,
+        time: Option<Time>,
+        limits: &SearchLimits,
+    ) -> Result<Position> {
+        match self.book.pick(game, &mut self.rng) {
+            Some(position) => {
+                println!("info book pv {:?}", vec![position.clone()]);
+                self.booked = true;
+                Ok(position)
+            }
+            None => {
+                self.booked = false;
+                self.inner.best(game, time, limits)
+            }
+        }
+    }
+
+    /// The inner player has nothing to ponder on after a move from the book.
+    fn ponder(&mut self, game: &Game) {
+        if !self.booked {
+            self.inner.ponder(game);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        heuristics::{Chance, Win},
+        lookers::All,
+        players::Thinker,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn opener() -> Result<Opener> {
+        let (_, game) = Game::parse("3_/3_/3_ x")?;
+        let mut book = Book::new(3, 3);
+        book.add(&game, &Position(1, 1), 1);
+        let thinker = Thinker::new(Box::new(Win::new(Box::new(Chance))), Box::new(All));
+        Ok(Opener::new(book, Box::new(thinker), Seeded::private(1)))
+    }
+
+    #[test]
+    fn test_plays_from_the_book() -> Result<()> {
+        let (_, mut game) = Game::parse("3_/3_/3_ x")?;
+        let position = opener()?.best(&mut game, None, &SearchLimits::default())?;
+        assert_eq!(position, Position(1, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_leaves_positions_out_of_book_to_inner_player() -> Result<()> {
+        let (_, mut game) = Game::parse("xx_/_o_/3_ o")?;
+        let position = opener()?.best(&mut game, None, &SearchLimits::default())?;
+        assert_eq!(position, Position(0, 2));
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use anyhow::{bail, Result};
+
+use crate::{
+    core::{Game, Position, SearchLimits, Time},
+    solvers::Perfect,
+};
+
+use super::Player;
+
+/// Plays perfectly by solving the game outright, so only for small boards.
+pub struct Oracle(Perfect);
+
+impl Default for Oracle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Oracle {
+    pub fn new() -> Self {
+        Self(Perfect::new())
+    }
+}
+
+impl Player for Oracle {
+    fn best(&mut self, game: &mut Game, _: Option<Time>, _: &SearchLimits) -> Result<Position> {
+        let (outcome, moves) = self.0.solve(game);
+        println!("info solved {:?} moves {:?}", outcome, moves);
+        match moves.first() {
+            Some(position) => Ok(position.clone()),
+            None => bail!("No moves left!"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristics::termination::Termination;
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_oracle_blocks_immediate_loss() -> Result<()> {
+        let (_, mut game) = Game::parse("xx_/_o_/3_ o")?;
+        let position = Oracle::new().best(&mut game, None, &SearchLimits::default())?;
+        assert_eq!(position, Position(0, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_oracle_never_loses_to_itself() -> Result<()> {
+        let (_, mut game) = Game::parse("3_/3_/3_ x")?;
+        let mut oracle = Oracle::new();
+        while !game.playable.is_empty() {
+            let position = oracle.best(&mut game, None, &SearchLimits::default())?;
+            game.play(&position);
+        }
+        assert_eq!(Termination::of(&game), Some(Termination::Drawn));
+        Ok(())
+    }
+}
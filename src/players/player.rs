@@ -1,7 +1,18 @@
 use anyhow::Result;
 
-use crate::core::{Game, Position, Time};
+use crate::core::{Game, Position, SearchLimits, Time};
 
 pub trait Player {
-    fn best(&mut self, game: &mut Game, time: Option<Time>) -> Result<Position>;
+    /// Picks a move within the time control, if any, and within the limits,
+    /// whichever runs out first.
+    fn best(
+        &mut self,
+        game: &mut Game,
+        time: Option<Time>,
+        limits: &SearchLimits,
+    ) -> Result<Position>;
+
+    /// Thinks about the game expected after the last best move until the
+    /// next call to `best`, for players that can.
+    fn ponder(&mut self, _: &Game) {}
 }
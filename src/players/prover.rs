@@ -0,0 +1,279 @@
+use std::time::Instant;
+
+use anyhow::{bail, Result};
+
+use crate::{
+    core::{Game, Position, SearchLimits, Side, Time},
+    heuristics::{termination::Termination, Assurance},
+    lookers::{All, Looker},
+    solvers::Outcome,
+};
+
+use super::Player;
+
+const INFINITY: u32 = u32::MAX;
+
+struct Node {
+    position: Option<Position>,
+    parent: usize,
+    children: Vec<usize>,
+    proof: u32,
+    disproof: u32,
+    attacking: bool,
+}
+
+impl Node {
+    fn new(position: Option<Position>, parent: usize, attacking: bool) -> Self {
+        Self {
+            position,
+            parent,
+            children: vec![],
+            proof: 1,
+            disproof: 1,
+            attacking,
+        }
+    }
+
+    fn settle(&mut self, proven: bool) {
+        (self.proof, self.disproof) = if proven { (0, INFINITY) } else { (INFINITY, 0) };
+    }
+}
+
+/// Proves or disproves a result for the side to play with proof-number search,
+/// first that it wins and then, failing that, that it does not lose.
+pub struct Prover {
+    looker: Box<dyn Looker>,
+    limit: usize,
+    nodes: Vec<Node>,
+}
+
+impl Prover {
+    pub fn new(looker: Box<dyn Looker>, limit: usize) -> Self {
+        Self {
+            looker,
+            limit,
+            nodes: vec![],
+        }
+    }
+
+    /// Whether the game is decided in favour of `attacker`, counting draws
+    /// as a success when asked to.
+    fn evaluate(game: &Game, attacker: &Side, draws: bool) -> Option<bool> {
+        match Termination::of(game) {
+            Some(Termination::Won(side)) => return Some(side == *attacker),
+            Some(Termination::Drawn) => return Some(draws),
+            None => {}
+        }
+        Assurance::of(game).map(|Assurance(side, _)| side == *attacker)
+    }
+
+    fn expand(&mut self, index: usize, game: &mut Game, attacker: &Side, draws: bool) {
+        let mut moves = self.looker.moves(game);
+        // A looker may have nothing to suggest on a board with cells left,
+        // like `Nearby` on an empty one, which is no draw.
+        if moves.is_empty() {
+            moves = All.moves(game);
+        }
+        if moves.is_empty() {
+            self.nodes[index].settle(draws);
+            return;
+        }
+        let attacking = !self.nodes[index].attacking;
+        for position in moves {
+            let mut node = Node::new(Some(position.clone()), index, attacking);
+            game.play(&position);
+            if let Some(proven) = Prover::evaluate(game, attacker, draws) {
+                node.settle(proven);
+            }
+            game.undo();
+            let child = self.nodes.len();
+            self.nodes[index].children.push(child);
+            self.nodes.push(node);
+        }
+    }
+
+    fn update(&mut self, index: usize) {
+        let node = &self.nodes[index];
+        let children = node.children.iter().map(|&child| &self.nodes[child]);
+        let (proof, disproof) = if node.attacking {
+            children.fold((INFINITY, 0u32), |(proof, disproof), child| {
+                (
+                    proof.min(child.proof),
+                    disproof.saturating_add(child.disproof),
+                )
+            })
+        } else {
+            children.fold((0u32, INFINITY), |(proof, disproof), child| {
+                (
+                    proof.saturating_add(child.proof),
+                    disproof.min(child.disproof),
+                )
+            })
+        };
+        self.nodes[index].proof = proof;
+        self.nodes[index].disproof = disproof;
+    }
+
+    /// Picks the child that is cheapest to prove at attacking nodes and
+    /// cheapest to disprove at defending ones.
+    fn most_proving(&self, index: usize) -> usize {
+        let node = &self.nodes[index];
+        let children = node.children.iter().copied();
+        if node.attacking {
+            children.min_by_key(|&child| self.nodes[child].proof)
+        } else {
+            children.min_by_key(|&child| self.nodes[child].disproof)
+        }
+        .expect("expanded")
+    }
+
+    fn search(&mut self, game: &mut Game, draws: bool, deadline: Option<Instant>) -> Option<bool> {
+        let attacker = game.side_to_play.clone();
+        self.nodes.clear();
+        self.nodes.push(Node::new(None, 0, true));
+        while self.nodes[0].proof != 0 && self.nodes[0].disproof != 0 {
+            if self.nodes.len() >= self.limit
+                || deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                return None;
+            }
+            let mut index = 0;
+            let mut depth = 0;
+            while !self.nodes[index].children.is_empty() {
+                index = self.most_proving(index);
+                let position = self.nodes[index].position.clone().expect("child");
+                game.play(&position);
+                depth += 1;
+            }
+            self.expand(index, game, &attacker, draws);
+            for _ in 0..depth {
+                game.undo();
+            }
+            loop {
+                if !self.nodes[index].children.is_empty() {
+                    self.update(index);
+                }
+                if index == 0 {
+                    break;
+                }
+                index = self.nodes[index].parent;
+            }
+        }
+        Some(self.nodes[0].proof == 0)
+    }
+
+    /// The root move that the last search found cheapest to prove.
+    fn proving(&self) -> Option<Position> {
+        if self.nodes.is_empty() || self.nodes[0].children.is_empty() {
+            return None;
+        }
+        self.nodes[self.most_proving(0)].position.clone()
+    }
+
+    pub fn prove(
+        &mut self,
+        game: &mut Game,
+        deadline: Option<Instant>,
+    ) -> (Outcome, Option<Position>) {
+        match self.search(game, false, deadline) {
+            Some(true) => return (Outcome::Won, self.proving()),
+            None => return (Outcome::Unknown, self.proving()),
+            Some(false) => {}
+        }
+        let fallback = self.proving();
+        match self.search(game, true, deadline) {
+            Some(true) => (Outcome::Drawn, self.proving()),
+            Some(false) => (Outcome::Lost, fallback),
+            None => (Outcome::Unknown, fallback),
+        }
+    }
+}
+
+impl Player for Prover {
+    fn best(
+        &mut self,
+        game: &mut Game,
+        time: Option<Time>,
+        limits: &SearchLimits,
+    ) -> Result<Position> {
+        let deadline = limits.deadline(Instant::now(), time.map(|time| time.budget(game)));
+        let limit = self.limit;
+        if let Some(nodes) = limits.nodes {
+            self.limit = limit.min(nodes as usize);
+        }
+        let (outcome, position) = self.prove(game, deadline);
+        self.limit = limit;
+        println!("info proof {:?} pv {:?}", outcome, position);
+        match position {
+            Some(position) => Ok(position),
+            None => bail!("No moves found!"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lookers::Nearby, solvers::solve};
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_prover_proves_immediate_win() -> Result<()> {
+        let (_, mut game) = Game::parse("xx_/oo_/3_ x")?;
+        let mut prover = Prover::new(Box::new(All), 100_000);
+        let proof = prover.prove(&mut game, None);
+        assert_eq!(proof, (Outcome::Won, Some(Position(0, 2))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_prover_proves_loss_against_double_threat() -> Result<()> {
+        let (_, mut game) = Game::parse("x_x/ox_/o2_ o")?;
+        let mut prover = Prover::new(Box::new(All), 100_000);
+        let (outcome, _) = prover.prove(&mut game, None);
+        assert_eq!(outcome, Outcome::Lost);
+        Ok(())
+    }
+
+    #[test]
+    fn test_prover_proves_empty_board_drawn() -> Result<()> {
+        let (_, mut game) = Game::parse("3_/3_/3_ x")?;
+        let mut prover = Prover::new(Box::new(All), 1_000_000);
+        let (outcome, position) = prover.prove(&mut game, None);
+        assert_eq!(outcome, Outcome::Drawn);
+        assert!(position.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_prover_finds_drawing_move() -> Result<()> {
+        let (_, mut game) = Game::parse("x2_/3_/3_ o")?;
+        let mut prover = Prover::new(Box::new(All), 1_000_000);
+        let (outcome, position) = prover.prove(&mut game, None);
+        assert_eq!((outcome, position), (Outcome::Drawn, Some(Position(1, 1))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_prover_searches_every_cell_when_looker_has_none() -> Result<()> {
+        let (_, mut game) = Game::parse("4_/4_/2_x_/4_ o")?;
+        game.set_win_length(3);
+        let mut prover = Prover::new(Box::new(Nearby::new(0)), 1_000_000);
+        let (outcome, position) = prover.prove(&mut game, None);
+        assert_eq!(outcome, solve(&game).0);
+        assert!(position.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_prover_is_unknown_when_out_of_nodes() -> Result<()> {
+        let (_, mut game) = Game::parse("5_/5_/5_/5_/5_ x")?;
+        game.set_win_length(4);
+        let mut prover = Prover::new(Box::new(Nearby::new(1)), 100);
+        let (outcome, position) = prover.prove(&mut game, None);
+        assert_eq!(outcome, Outcome::Unknown);
+        assert!(position.is_some());
+        Ok(())
+    }
+}
@@ -1,18 +1,18 @@
 use anyhow::{bail, Result};
-use rand::{rngs::ThreadRng, Rng};
+use rand::Rng;
 
 use crate::{
-    core::{Game, Position, Time},
+    core::{Game, Position, SearchLimits, Seeded, Time},
     lookers::Looker,
 };
 
 use super::Player;
 
-pub struct Random(pub Box<dyn Looker>, pub ThreadRng);
+pub struct Random(pub Box<dyn Looker>, pub Seeded);
 
 impl Player for Random {
-    fn best(&mut self, game: &mut Game, _: Option<Time>) -> Result<Position> {
-        let moves = self.0.moves(&game);
+    fn best(&mut self, game: &mut Game, _: Option<Time>, _: &SearchLimits) -> Result<Position> {
+        let moves = self.0.moves(game);
         let count = moves.len();
         if count == 0 {
             bail!("No moves left!");
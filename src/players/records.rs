@@ -0,0 +1,270 @@
+use std::{fmt::Display, path::Path};
+
+use anyhow::{bail, Result};
+use nom::{
+    bytes::complete::tag,
+    character::complete::u32,
+    combinator::map,
+    sequence::{terminated, tuple},
+    IResult,
+};
+use rustc_hash::FxHashMap;
+
+use crate::{
+    core::{Game, Position},
+    hashers::{inverse, Transposer, SYMMETRIES},
+    heuristics::termination::Termination,
+};
+
+use super::{
+    book::{load_text, parse_entries, save_text, write_entries, Entries},
+    Book,
+};
+
+/// How the games went for the side that played a move.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+impl Tally {
+    pub fn games(&self) -> u32 {
+        self.wins + self.losses + self.draws
+    }
+
+    /// Two points for a win and one for a draw.
+    pub fn points(&self) -> u32 {
+        2 * self.wins + self.draws
+    }
+
+    fn parse(input: &str) -> IResult<&str, Tally> {
+        map(
+            tuple((terminated(u32, tag("/")), terminated(u32, tag("/")), u32)),
+            |(wins, losses, draws)| Tally {
+                wins,
+                losses,
+                draws,
+            },
+        )(input)
+    }
+}
+
+impl Display for Tally {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}/{}", self.wins, self.losses, self.draws)
+    }
+}
+
+/// How finished games went after each move in their openings, gathered from
+/// self-play and game records to build a book from. Symmetric positions share
+/// their tallies just as they share book entries.
+///
+/// Records are written as text, a `records <size> <win-length>` line followed
+/// by a line per position with its hash in hex and its moves with their wins,
+/// losses and draws, like `5f1c07e2a9d3b804 h8 7/2/3 i9 0/1/0`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Records {
+    size: u8,
+    win_length: u8,
+    entries: Entries<Tally>,
+}
+
+/// The canonical hash of the game after the move.
+fn after(game: &mut Game, position: &Position) -> u64 {
+    game.play(position);
+    let (hash, _) = Transposer.canonical(game);
+    game.undo();
+    hash
+}
+
+impl Records {
+    pub fn new(size: u8, win_length: u8) -> Self {
+        Self {
+            size,
+            win_length,
+            entries: FxHashMap::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Tallies the first few moves of a game played out from the one given,
+    /// for whoever played each of them.
+    pub fn record(&mut self, game: &Game, moves: &[Position], plies: usize) -> Result<()> {
+        if (game.size, game.win_length) != (self.size, self.win_length) {
+            bail!("game is not played under the rules of the records");
+        }
+        let mut played = game.clone();
+        for position in moves {
+            if Termination::of(&played).is_some() {
+                bail!("game is over before {}", position);
+            }
+            if !played.playable.contains(position) {
+                bail!("{} is not playable", position);
+            }
+            played.play(position);
+        }
+        let winner = match Termination::of(&played) {
+            Some(Termination::Won(side)) => Some(side),
+            Some(Termination::Drawn) => None,
+            None => bail!("game is not over"),
+        };
+        let mut replayed = game.clone();
+        for position in moves.iter().take(plies) {
+            let (hash, symmetry) = Transposer.canonical(&replayed);
+            let back = SYMMETRIES[inverse(symmetry)];
+            let child = after(&mut replayed, position);
+            let tallies = self.entries.entry(hash).or_default();
+            // Moves to cells that are turns or flips of each other on a
+            // symmetric board lead to the same game, so they share a tally.
+            let index = match tallies
+                .iter()
+                .position(|(known, _)| after(&mut replayed, &back(known, game.size)) == child)
+            {
+                Some(index) => index,
+                None => {
+                    let canonical = SYMMETRIES[symmetry](position, game.size);
+                    tallies.push((canonical, Tally::default()));
+                    tallies.len() - 1
+                }
+            };
+            let tally = &mut tallies[index].1;
+            match &winner {
+                Some(side) if *side == replayed.side_to_play => tally.wins += 1,
+                Some(_) => tally.losses += 1,
+                None => tally.draws += 1,
+            }
+            replayed.play(position);
+        }
+        Ok(())
+    }
+
+    /// A book of the moves played in at least as many games as given that
+    /// scored at least the percentage of points given, weighted by points.
+    pub fn book(&self, min_games: u32, min_score: u8) -> Book {
+        let mut book = Book::new(self.size, self.win_length);
+        for (hash, tallies) in &self.entries {
+            for (position, tally) in tallies {
+                let games = tally.games();
+                if games == 0 || games < min_games {
+                    continue;
+                }
+                if tally.points() as u64 * 100 < min_score as u64 * 2 * games as u64 {
+                    continue;
+                }
+                if tally.points() > 0 {
+                    book.insert(*hash, position.clone(), tally.points());
+                }
+            }
+        }
+        book
+    }
+
+    pub fn parse(input: &str) -> IResult<&str, Records> {
+        let (remaining, (size, win_length, entries)) =
+            parse_entries("records", Tally::parse, input)?;
+        let records = Records {
+            size,
+            win_length,
+            entries,
+        };
+        Ok((remaining, records))
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        load_text(path, "records", Records::parse)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        save_text(path, self)
+    }
+}
+
+impl Display for Records {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_entries(f, "records", self.size, self.win_length, &self.entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn moves(input: &str) -> Vec<Position> {
+        input
+            .split_whitespace()
+            .map(|position| Position::parse(position).expect("parsed").1)
+            .collect()
+    }
+
+    #[test]
+    fn test_tallies_each_move_for_its_player() -> Result<()> {
+        let (_, game) = Game::parse("3_/3_/3_ x")?;
+        let mut records = Records::new(3, 3);
+        records.record(&game, &moves("a1 b1 a2 b2 a3"), 2)?;
+        let book = records.book(0, 0);
+        assert_eq!(book.moves(&game), vec![(Position(0, 0), 2)]);
+        let mut replied = game.clone();
+        replied.play(&Position(0, 0));
+        assert_eq!(book.moves(&replied), vec![]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merges_symmetric_openings() -> Result<()> {
+        let (_, game) = Game::parse("3_/3_/3_ x")?;
+        let mut records = Records::new(3, 3);
+        records.record(&game, &moves("a1 b1 a2 b2 a3"), 1)?;
+        records.record(&game, &moves("c3 b3 c2 b2 c1"), 1)?;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records.book(2, 0).moves(&game), vec![(Position(0, 0), 4)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_leaves_out_rare_and_losing_moves() -> Result<()> {
+        let (_, game) = Game::parse("3_/3_/3_ x")?;
+        let mut records = Records::new(3, 3);
+        records.record(&game, &moves("a1 b1 a2 b2 c3 b3"), 1)?;
+        records.record(&game, &moves("b2 a1 c3 c1 a3 b1"), 1)?;
+        records.record(&game, &moves("b2 a1 c3 c1 a3 b1"), 1)?;
+        assert_eq!(records.book(0, 50).moves(&game), vec![]);
+        records.record(&game, &moves("b2 a2 a1 b1 c3"), 1)?;
+        assert_eq!(records.book(3, 0).moves(&game), vec![(Position(1, 1), 2)]);
+        assert_eq!(records.book(0, 40).moves(&game), vec![]);
+        assert_eq!(records.book(0, 25).moves(&game), vec![(Position(1, 1), 2)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_unfinished_games() -> Result<()> {
+        let (_, game) = Game::parse("3_/3_/3_ x")?;
+        let mut records = Records::new(3, 3);
+        assert!(records.record(&game, &moves("a1 b1"), 2).is_err());
+        assert!(records.record(&game, &moves("a1 a1"), 2).is_err());
+        assert!(records.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reads_what_it_writes() -> Result<()> {
+        let (_, game) = Game::parse("3_/3_/3_ x")?;
+        let mut records = Records::new(3, 3);
+        records.record(&game, &moves("a1 b1 a2 b2 a3"), 3)?;
+        records.record(&game, &moves("b2 a2 a1 b1 c3"), 3)?;
+        let written = records.to_string();
+        let (remaining, read) = Records::parse(&written).expect("parsed");
+        assert_eq!(remaining, "");
+        assert_eq!(read, records);
+        Ok(())
+    }
+}
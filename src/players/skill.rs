@@ -0,0 +1,167 @@
+use rand::Rng;
+
+use crate::{
+    core::{Position, SearchLimits},
+    heuristics::Score,
+};
+
+/// The level a `Thinker` plays at full strength.
+pub const MAX_SKILL: u8 = 20;
+
+/// How well a `Thinker` plays, from 0 for someone new to the game up to
+/// `MAX_SKILL`. Lower levels search shallower and fewer nodes, and now and
+/// then play one of the next best moves at the root instead of the best.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Skill(u8);
+
+impl Default for Skill {
+    fn default() -> Self {
+        Skill(MAX_SKILL)
+    }
+}
+
+impl Skill {
+    pub fn new(level: u8) -> Self {
+        Skill(level.min(MAX_SKILL))
+    }
+
+    pub fn level(&self) -> u8 {
+        self.0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.0 == MAX_SKILL
+    }
+
+    /// The limits given, tightened to what the level allows, from a ply and
+    /// 200 nodes at level 0 to 5 plies and about 100,000 nodes at level 19.
+    /// They only ever come on top of the time control, which still holds.
+    pub fn limit(&self, limits: &SearchLimits) -> SearchLimits {
+        if self.is_full() {
+            return limits.clone();
+        }
+        let depth = 1 + self.0 / 4;
+        let nodes = 200u64 << (self.0 / 2);
+        SearchLimits {
+            depth: Some(limits.depth.map_or(depth, |limit| limit.min(depth))),
+            nodes: Some(limits.nodes.map_or(nodes, |limit| limit.min(nodes))),
+            time: limits.time,
+        }
+    }
+
+    /// How many of the best root moves the level may choose from.
+    pub fn candidates(&self) -> usize {
+        1 + (MAX_SKILL - self.0) as usize / 5
+    }
+
+    /// How often the level passes over the best move, one in two at level 0.
+    pub fn lapse(&self) -> f64 {
+        (MAX_SKILL - self.0) as f64 / 40.0
+    }
+
+    /// The index of the variation to play out of the root variations given,
+    /// best first, in a game with as many moves played as given. Moves that
+    /// lose for sure are never played over one that does not, nor is a win
+    /// on the spot passed over.
+    pub fn choose<R: Rng>(
+        &self,
+        variations: &[(Vec<Position>, Score)],
+        played: usize,
+        rng: &mut R,
+    ) -> usize {
+        let best = match variations.first() {
+            Some((_, score)) => *score,
+            None => return 0,
+        };
+        if let Score::Win(moves) = best {
+            if moves as usize <= played + 1 {
+                return 0;
+            }
+        }
+        let plausible: Vec<usize> = variations
+            .iter()
+            .enumerate()
+            .take(self.candidates())
+            .skip(1)
+            .filter(|(_, (_, score))| {
+                matches!(best, Score::Loss(_)) || !matches!(score, Score::Loss(_))
+            })
+            .map(|(index, _)| index)
+            .collect();
+        if plausible.is_empty() || !rng.gen_bool(self.lapse()) {
+            return 0;
+        }
+        plausible[rng.gen_range(0..plausible.len())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn variations(scores: &[Score]) -> Vec<(Vec<Position>, Score)> {
+        scores
+            .iter()
+            .enumerate()
+            .map(|(index, score)| (vec![Position(0, index as u8)], *score))
+            .collect()
+    }
+
+    #[test]
+    fn test_full_skill_leaves_limits_and_plays_best() {
+        let skill = Skill::default();
+        let limits = SearchLimits::default();
+        assert_eq!(skill.limit(&limits), limits);
+        let variations = variations(&[Score::Value(5), Score::Value(4)]);
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..50 {
+            assert_eq!(skill.choose(&variations, 0, &mut rng), 0);
+        }
+    }
+
+    #[test]
+    fn test_lower_skill_searches_less() {
+        let limits = SearchLimits {
+            depth: Some(3),
+            ..SearchLimits::default()
+        };
+        let beginner = Skill::new(0).limit(&limits);
+        assert_eq!(beginner.depth, Some(1));
+        assert_eq!(beginner.nodes, Some(200));
+        let strong = Skill::new(19).limit(&limits);
+        assert_eq!(strong.depth, Some(3));
+        assert_eq!(strong.nodes, Some(102_400));
+    }
+
+    #[test]
+    fn test_beginner_sometimes_plays_plausible_moves() {
+        let skill = Skill::new(0);
+        let variations = variations(&[
+            Score::Value(5),
+            Score::Value(4),
+            Score::Loss(7),
+            Score::Value(-2),
+        ]);
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut chosen = [0; 4];
+        for _ in 0..400 {
+            chosen[skill.choose(&variations, 0, &mut rng)] += 1;
+        }
+        assert!(chosen[0] > 100);
+        assert!(chosen[1] > 0);
+        assert_eq!(chosen[2], 0);
+        assert!(chosen[3] > 0);
+    }
+
+    #[test]
+    fn test_beginner_never_misses_a_win_on_the_spot() {
+        let skill = Skill::new(0);
+        let variations = variations(&[Score::Win(5), Score::Value(4)]);
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..50 {
+            assert_eq!(skill.choose(&variations, 4, &mut rng), 0);
+        }
+    }
+}
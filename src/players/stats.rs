@@ -0,0 +1,63 @@
+use std::{fmt::Display, time::Duration};
+
+/// What a search had done by the end of an iteration.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub nodes: u64,
+    pub table_hits: u64,
+    pub cache_hits: u64,
+    pub cutoffs: u64,
+    /// Nodes searched in the iteration over nodes searched in the one before,
+    /// or zero for the first.
+    pub branching: f64,
+    pub elapsed: Duration,
+}
+
+impl Stats {
+    /// Nodes per second.
+    pub fn nps(&self) -> u64 {
+        match self.elapsed.as_micros() {
+            0 => 0,
+            micros => (self.nodes as u128 * 1_000_000 / micros) as u64,
+        }
+    }
+}
+
+/// The counts in the order ST3P `info` lines give them, time in milliseconds.
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "nodes {} nps {} time {} hashhits {} cachehits {} cutoffs {} ebf {:.2}",
+            self.nodes,
+            self.nps(),
+            self.elapsed.as_millis(),
+            self.table_hits,
+            self.cache_hits,
+            self.cutoffs,
+            self.branching
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_displays_every_count() {
+        let stats = Stats {
+            nodes: 3_000,
+            table_hits: 120,
+            cache_hits: 45,
+            cutoffs: 300,
+            branching: 3.456,
+            elapsed: Duration::from_millis(1_500),
+        };
+        assert_eq!(
+            stats.to_string(),
+            "nodes 3000 nps 2000 time 1500 hashhits 120 cachehits 45 cutoffs 300 ebf 3.46"
+        );
+    }
+}
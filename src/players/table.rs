@@ -0,0 +1,204 @@
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+
+use crate::{core::Position, solvers::Bound};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub hash: u64,
+    pub depth: u8,
+    pub bound: Bound,
+    pub score: i64,
+    pub best: Option<Position>,
+    generation: u8,
+}
+
+impl Entry {
+    /// Packs everything but the hash and the score into one word that is
+    /// never zero, so that a zeroed slot reads as empty.
+    fn pack(&self) -> u64 {
+        let bound = match self.bound {
+            Bound::Exact => 1,
+            Bound::Lower => 2,
+            Bound::Upper => 3,
+        };
+        let best = match &self.best {
+            Some(Position(row, column)) => 1 << 24 | (*row as u64) << 32 | (*column as u64) << 40,
+            None => 0,
+        };
+        self.depth as u64 | bound << 8 | (self.generation as u64) << 16 | best
+    }
+
+    fn unpack(hash: u64, score: u64, data: u64) -> Option<Entry> {
+        let bound = match (data >> 8) & 0b11 {
+            1 => Bound::Exact,
+            2 => Bound::Lower,
+            3 => Bound::Upper,
+            _ => return None,
+        };
+        let best = if data & 1 << 24 != 0 {
+            Some(Position((data >> 32) as u8, (data >> 40) as u8))
+        } else {
+            None
+        };
+        Some(Entry {
+            hash,
+            depth: data as u8,
+            bound,
+            score: score as i64,
+            best,
+            generation: (data >> 16) as u8,
+        })
+    }
+}
+
+/// A fixed size transposition table indexed by `Game::hash` that threads can
+/// share without locking.
+///
+/// Each slot keeps the hash xor-ed with the rest of the entry, so an entry
+/// torn by two threads writing at once no longer matches its hash and is
+/// ignored. A slot is replaced when it is empty, holds the same position,
+/// was written during an earlier search or was searched no deeper than the
+/// new entry.
+pub struct Table {
+    entries: Vec<[AtomicU64; 3]>,
+    mask: usize,
+    generation: AtomicU8,
+}
+
+impl Table {
+    pub fn new(size: usize) -> Self {
+        let size = match size.max(1) {
+            size if size.is_power_of_two() => size,
+            size => size.next_power_of_two() / 2,
+        };
+        Self {
+            entries: (0..size).map(|_| Default::default()).collect(),
+            mask: size - 1,
+            generation: AtomicU8::new(0),
+        }
+    }
+
+    /// Empties every slot, for when what was stored no longer holds.
+    pub fn clear(&self) {
+        for slot in &self.entries {
+            for word in slot {
+                word.store(0, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Marks every entry as belonging to an earlier search.
+    pub fn age(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn read(&self, hash: u64) -> Option<Entry> {
+        let [check, score, data] = &self.entries[hash as usize & self.mask];
+        let check = check.load(Ordering::Relaxed);
+        let score = score.load(Ordering::Relaxed);
+        let data = data.load(Ordering::Relaxed);
+        Entry::unpack(check ^ score ^ data, score, data)
+    }
+
+    pub fn probe(&self, hash: u64) -> Option<Entry> {
+        self.read(hash).filter(|entry| entry.hash == hash)
+    }
+
+    pub fn store(&self, hash: u64, depth: u8, bound: Bound, score: i64, best: Option<Position>) {
+        let generation = self.generation.load(Ordering::Relaxed);
+        let replace = match self.read(hash) {
+            None => true,
+            Some(entry) => {
+                entry.hash == hash || entry.generation != generation || entry.depth <= depth
+            }
+        };
+        if !replace {
+            return;
+        }
+        let entry = Entry {
+            hash,
+            depth,
+            bound,
+            score,
+            best,
+            generation,
+        };
+        let data = entry.pack();
+        let score = score as u64;
+        let [check_slot, score_slot, data_slot] = &self.entries[hash as usize & self.mask];
+        check_slot.store(hash ^ score ^ data, Ordering::Relaxed);
+        score_slot.store(score, Ordering::Relaxed);
+        data_slot.store(data, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_table_size_is_a_power_of_two() {
+        assert_eq!(Table::new(1000).entries.len(), 512);
+        assert_eq!(Table::new(1024).entries.len(), 1024);
+    }
+
+    #[test]
+    fn test_probe_returns_stored_entry() {
+        let table = Table::new(16);
+        table.store(42, 3, Bound::Exact, -7, Some(Position(1, 14)));
+        let entry = table.probe(42).expect("stored");
+        assert_eq!(
+            (entry.depth, entry.bound, entry.score, entry.best),
+            (3, Bound::Exact, -7, Some(Position(1, 14)))
+        );
+    }
+
+    #[test]
+    fn test_probe_keeps_extreme_scores() {
+        let table = Table::new(16);
+        table.store(42, 3, Bound::Lower, i64::MAX - 9, None);
+        assert_eq!(table.probe(42).map(|entry| entry.score), Some(i64::MAX - 9));
+    }
+
+    #[test]
+    fn test_probe_ignores_other_position_in_same_slot() {
+        let table = Table::new(16);
+        table.store(42, 3, Bound::Exact, 7, None);
+        assert_eq!(table.probe(42 + 16), None);
+    }
+
+    #[test]
+    fn test_probe_ignores_torn_entry() {
+        let table = Table::new(16);
+        table.store(42, 3, Bound::Exact, 7, None);
+        table.entries[42 & 15][1].store(8, Ordering::Relaxed);
+        assert_eq!(table.probe(42), None);
+    }
+
+    #[test]
+    fn test_clear_forgets_every_entry() {
+        let table = Table::new(16);
+        table.store(42, 3, Bound::Exact, 7, None);
+        table.clear();
+        assert_eq!(table.probe(42), None);
+    }
+
+    #[test]
+    fn test_store_keeps_deeper_entry_from_same_search() {
+        let table = Table::new(16);
+        table.store(42, 5, Bound::Lower, 7, None);
+        table.store(42 + 16, 2, Bound::Upper, 3, None);
+        assert_eq!(table.probe(42).map(|entry| entry.depth), Some(5));
+    }
+
+    #[test]
+    fn test_store_replaces_entry_from_earlier_search() {
+        let table = Table::new(16);
+        table.store(42, 5, Bound::Lower, 7, None);
+        table.age();
+        table.store(42 + 16, 2, Bound::Upper, 3, None);
+        assert_eq!(table.probe(42), None);
+        assert_eq!(table.probe(42 + 16).map(|entry| entry.depth), Some(2));
+    }
+}
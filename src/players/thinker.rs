@@ -1,108 +1,795 @@
+use std::{
+    cmp::Reverse,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Instant,
+};
+
 use crate::{
-    core::{Game, Position, Side, Time},
-    heuristics::{termination::Termination, Assurance, Heuristic},
+    core::{Game, Position, SearchLimits, Seeded, Side, Time},
+    hashers::{distinct, equivalent, Transposer},
+    heuristics::{
+        termination::Termination,
+        threats::{threatens, winning_cells},
+        Assurance, Contempt, Heuristic, Score,
+    },
     lookers::Looker,
+    solvers::{Bound, Database, Outcome, Solution, Vcf},
 };
 use anyhow::{bail, Result};
 
-use super::Player;
+use super::{History, Killers, Player, Skill, Stats, Table};
+
+/// Entries in the transposition table of a `Thinker`.
+pub const TABLE_SIZE: usize = 1 << 20;
+
+/// Plies of forcing moves that quiescence search may add past the horizon.
+pub const QUIESCENCE_DEPTH: u8 = 16;
+
+/// Moves searched at full depth before the quiet ones start to be reduced.
+pub const LATE_MOVES: usize = 3;
+
+/// Nodes the VCF solver may visit before the regular search takes over.
+pub const VCF_BUDGET: usize = 10_000;
 
 #[inline(always)]
 fn is_terminal(game: &Game) -> bool {
     Termination::of(game).is_some() || Assurance::of(game).is_some()
 }
 
-pub struct Thinker(Box<dyn Heuristic>, Box<dyn Looker>, u8);
+/// Where a `Thinker` leaves out moves that a turn or flip of a symmetric
+/// board takes onto moves it already searches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SymmetryPruning {
+    Off,
+    /// Only at the root, where symmetric positions like the empty board or
+    /// a centre opening come up most.
+    Root,
+    /// At the root and at every node below it that was reached through
+    /// symmetric positions only.
+    Tree,
+}
+
+/// Builds the heuristic and looker for a search thread, on that thread.
+pub type Factory = Arc<dyn Fn() -> (Box<dyn Heuristic>, Box<dyn Looker>) + Send + Sync>;
+
+struct Pondering {
+    hash: u64,
+    stop: Arc<AtomicBool>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Drop for Pondering {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+pub struct Thinker {
+    heuristic: Box<dyn Heuristic>,
+    looker: Box<dyn Looker>,
+    depth: u8,
+    table: Arc<Table>,
+    rules: (u8, u8),
+    draw: Score,
+    killers: Killers,
+    history: History,
+    vcf: Vcf,
+    factory: Option<Factory>,
+    database: Option<Arc<Database>>,
+    threads: usize,
+    stop: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+    nodes: u64,
+    table_hits: u64,
+    cutoffs: u64,
+    reductions: u64,
+    stats: Stats,
+    max_nodes: Option<u64>,
+    stopped: bool,
+    pv: Vec<Position>,
+    pondering: Option<Pondering>,
+    multi_pv: usize,
+    skill: Skill,
+    contempt: Arc<Contempt>,
+    rng: Seeded,
+    selective: bool,
+    reach: usize,
+    symmetry: SymmetryPruning,
+    symmetric: usize,
+    excluded: Vec<Position>,
+    variations: Vec<(Vec<Position>, Score)>,
+}
 
 impl Thinker {
     pub fn new(heuristic: Box<dyn Heuristic>, looker: Box<dyn Looker>) -> Self {
-        Self(heuristic, looker, 2)
+        Self::with_depth(heuristic, looker, 2)
     }
 
     pub fn with_depth(heuristic: Box<dyn Heuristic>, looker: Box<dyn Looker>, depth: u8) -> Self {
-        Self(heuristic, looker, depth)
+        Self::build(
+            heuristic,
+            looker,
+            depth,
+            Arc::new(Table::new(TABLE_SIZE)),
+            Arc::new(AtomicBool::new(false)),
+        )
     }
 
-    pub fn pvs(
+    /// Searches with as many threads as asked for, each with a heuristic and
+    /// looker of its own from the factory, sharing one transposition table.
+    pub fn parallel(factory: Factory, depth: u8, threads: usize) -> Self {
+        let (heuristic, looker) = factory();
+        Self {
+            factory: Some(factory),
+            threads: threads.max(1),
+            ..Self::with_depth(heuristic, looker, depth)
+        }
+    }
+
+    fn build(
+        heuristic: Box<dyn Heuristic>,
+        looker: Box<dyn Looker>,
+        depth: u8,
+        table: Arc<Table>,
+        stop: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            heuristic,
+            looker,
+            depth,
+            table,
+            rules: (0, 0),
+            draw: Score::Draw,
+            killers: Killers::default(),
+            history: History::default(),
+            vcf: Vcf::new(VCF_BUDGET),
+            factory: None,
+            database: None,
+            threads: 1,
+            stop,
+            deadline: None,
+            nodes: 0,
+            table_hits: 0,
+            cutoffs: 0,
+            reductions: 0,
+            stats: Stats::default(),
+            max_nodes: None,
+            stopped: false,
+            pv: vec![],
+            pondering: None,
+            multi_pv: 1,
+            skill: Skill::default(),
+            contempt: Arc::default(),
+            rng: Seeded::new(),
+            selective: true,
+            reach: 0,
+            symmetry: SymmetryPruning::Tree,
+            symmetric: 0,
+            excluded: vec![],
+            variations: vec![],
+        }
+    }
+
+    /// Plays from the solution database instead of searching whenever it
+    /// has the game.
+    pub fn set_database(&mut self, database: Arc<Database>) {
+        self.database = Some(database);
+    }
+
+    pub fn set_symmetry_pruning(&mut self, symmetry: SymmetryPruning) {
+        self.symmetry = symmetry;
+    }
+
+    /// Scores the draws it finds by the contempt given, which should be the
+    /// one its heuristics score draws by, and tells it which side it plays.
+    /// Its helper and pondering threads score draws by the same contempt.
+    pub fn set_contempt(&mut self, contempt: Arc<Contempt>) {
+        self.contempt = contempt;
+    }
+
+    /// Plays weaker than it can, for players still learning the game. Below
+    /// full strength the solution database and the VCF solver are left out,
+    /// as no beginner finds their moves.
+    pub fn set_skill(&mut self, skill: Skill) {
+        self.skill = skill;
+    }
+
+    /// Only takes effect for a `Thinker` made with `parallel`, as the others
+    /// have no way to build a heuristic for another thread.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Counts the node about to be searched and tells whether the search
+    /// has to stop instead, for time, for nodes or because it was told to.
+    #[inline(always)]
+    fn out_of_time(&mut self) -> bool {
+        self.nodes += 1;
+        if !self.stopped {
+            self.stopped = self.stop.load(Ordering::Relaxed)
+                || self.max_nodes.is_some_and(|max| self.nodes > max)
+                || self
+                    .deadline
+                    .is_some_and(|deadline| Instant::now() >= deadline);
+        }
+        self.stopped
+    }
+
+    /// Scores the game from the point of view of the side to play.
+    #[inline(always)]
+    fn evaluate(&mut self, game: &Game) -> Score {
+        let score = self.heuristic.score(game);
+        match game.side_to_play {
+            Side::X => score,
+            Side::O => -score,
+        }
+    }
+
+    /// Scores a draw from the point of view of the side to play.
+    #[inline(always)]
+    fn draw(&self, game: &Game) -> Score {
+        let score = self.contempt.draw();
+        match game.side_to_play {
+            Side::X => score,
+            Side::O => -score,
+        }
+    }
+
+    /// Puts the table move first, then the killers for the ply, then the
+    /// rest by history, leaving ties in the order the looker gave them.
+    fn order(&self, moves: &mut [Position], hint: Option<Position>, ply: usize, side: &Side) {
+        let killers = self.killers.get(ply);
+        moves.sort_by_cached_key(|position| {
+            if hint.as_ref() == Some(position) {
+                return Reverse(u64::MAX);
+            }
+            match killers.iter().position(|x| x.as_ref() == Some(position)) {
+                Some(index) => Reverse(u64::MAX - 1 - index as u64),
+                None => Reverse(self.history.get(position, side)),
+            }
+        });
+    }
+
+    /// Plays on past the horizon with only the forcing moves, a win for the
+    /// side to play or else the blocks of the opponent's wins, and scores the
+    /// game once neither side threatens to win.
+    pub fn quiesce(
         &mut self,
         game: &mut Game,
         visited: usize,
+        mut alpha: Score,
+        beta: Score,
         depth: u8,
-        mut alpha: i64,
-        mut beta: i64,
-        maximizing: bool,
-    ) -> (Vec<Position>, i64) {
+    ) -> (Vec<Position>, Score) {
         let mut best = game.moves[visited..].to_vec();
+        if self.out_of_time() {
+            return (best, Score::Value(0));
+        }
+        if !game.winnable() {
+            return (best, self.draw(game));
+        }
         if depth == 0 || is_terminal(game) {
-            return (best, self.0.score(&game));
-        }
-
-        if maximizing {
-            let mut value = i64::MIN;
-            for position in self.1.moves(&game) {
-                game.play(&position);
-                let (mut pv, score) = self.pvs(game, visited + 1, depth - 1, alpha, beta, false);
-                game.undo();
-                if score > value {
-                    pv.insert(0, position.clone());
-                    best = pv;
-                    value = score;
-                }
-                alpha = alpha.max(value);
-                if alpha >= beta {
-                    break; // Beta cut-off
+            return (best, self.evaluate(game));
+        }
+        let side = game.side_to_play.clone();
+        if let Some(position) = winning_cells(game, &side).first() {
+            game.play(position);
+            let (pv, score) = self.quiesce(game, visited, -beta, -alpha, depth - 1);
+            game.undo();
+            return (pv, -score);
+        }
+        let blocks = winning_cells(game, &side.other());
+        if blocks.is_empty() {
+            return (best, self.evaluate(game));
+        }
+        let mut value = Score::MIN;
+        for position in blocks {
+            game.play(&position);
+            let (pv, score) = self.quiesce(game, visited, -beta, -alpha, depth - 1);
+            game.undo();
+            if self.stopped {
+                break;
+            }
+            if -score > value {
+                best = pv;
+                value = -score;
+            }
+            alpha = alpha.max(value);
+            if alpha >= beta {
+                break;
+            }
+        }
+        (best, value)
+    }
+
+    pub fn pvs(
+        &mut self,
+        game: &mut Game,
+        visited: usize,
+        depth: u8,
+        mut alpha: Score,
+        beta: Score,
+    ) -> (Vec<Position>, Score) {
+        let mut best = game.moves[visited..].to_vec();
+        if self.out_of_time() {
+            return (best, Score::Value(0));
+        }
+        // Nobody can get a line any more, so there is nothing to search for,
+        // though the root still has to come up with a move.
+        if !game.winnable() && visited < game.moves.len() {
+            return (best, self.draw(game));
+        }
+        if is_terminal(game) {
+            return (best, self.evaluate(game));
+        }
+        if depth == 0 {
+            return self.quiesce(game, visited, alpha, beta, QUIESCENCE_DEPTH);
+        }
+        let mut hint = None;
+        if let Some(entry) = self.table.probe(game.hash) {
+            self.table_hits += 1;
+            // The root has to come up with a move, so it never stops here.
+            if entry.depth >= depth && visited < game.moves.len() {
+                let cutoff = match entry.bound {
+                    Bound::Exact => true,
+                    Bound::Lower => Score::from(entry.score) >= beta,
+                    Bound::Upper => Score::from(entry.score) <= alpha,
+                };
+                if cutoff {
+                    best.extend(entry.best.clone());
+                    return (best, Score::from(entry.score));
                 }
             }
-            return (best, value);
+            hint = entry.best.clone();
+        }
+        let ply = game.moves.len() - visited;
+        // Every position before the first `symmetric` plies of the line being
+        // searched is symmetric, so the ones after can only be symmetric if
+        // they come right after.
+        let check = match self.symmetry {
+            SymmetryPruning::Off => false,
+            SymmetryPruning::Root => ply == 0,
+            SymmetryPruning::Tree => ply == 0 || self.symmetric >= ply,
+        };
+        let symmetries = match check {
+            true => Transposer.symmetries(game),
+            false => vec![0],
+        };
+        self.symmetric = match check {
+            true => ply + (symmetries.len() > 1) as usize,
+            false => self.symmetric.min(ply),
+        };
+        let mut moves = self.looker.moves(game);
+        if ply == 0 && !self.excluded.is_empty() {
+            let excluded = &self.excluded;
+            moves.retain(|position| {
+                !excluded
+                    .iter()
+                    .any(|known| equivalent(position, known, &symmetries, game.size))
+            });
+            if moves.is_empty() {
+                return (best, Score::MIN);
+            }
+        }
+        if moves.is_empty() {
+            return (best, self.evaluate(game));
         }
+        self.order(&mut moves, hint, ply, &game.side_to_play);
+        // Of moves that lead to the same game up to a turn or flip, only the
+        // first in order is worth searching.
+        let moves = distinct(moves, &symmetries, game.size);
 
-        let mut value = i64::MAX;
-        for position in self.1.moves(&game) {
+        if ply == 0 {
+            self.reach = 2 * depth as usize;
+        }
+        let blocks = winning_cells(game, &game.side_to_play.other());
+        let original = alpha;
+        let mut value = Score::MIN;
+        for (index, position) in moves.into_iter().enumerate() {
+            let answers = blocks.contains(&position);
             game.play(&position);
-            let (mut pv, score) = self.pvs(game, visited + 1, depth - 1, alpha, beta, true);
+            // Threats and answers to them are searched a ply deeper, up to
+            // twice the depth of the iteration, while late quiet moves are
+            // searched a ply shallower unless they turn out to beat alpha.
+            let forcing = self.selective && (answers || threatens(game, &position));
+            let next = if forcing && ply < self.reach {
+                depth
+            } else {
+                depth - 1
+            };
+            let reduction = if self.selective && !forcing && index >= LATE_MOVES && depth >= 3 {
+                1
+            } else {
+                0
+            };
+            let (mut pv, mut score) = if index == 0 {
+                self.pvs(game, visited, next, -beta, -alpha)
+            } else {
+                self.pvs(game, visited, next - reduction, -alpha.next(), -alpha)
+            };
+            score = -score;
+            self.reductions += reduction as u64;
+            if reduction > 0 && score > alpha && !self.stopped {
+                (pv, score) = self.pvs(game, visited, next, -alpha.next(), -alpha);
+                score = -score;
+            }
+            if index > 0 && score > alpha && score < beta && !self.stopped {
+                // The null window failed high, so this move may be better
+                // than the principal variation and needs its exact score.
+                (pv, score) = self.pvs(game, visited, next, -beta, -alpha);
+                score = -score;
+            }
             game.undo();
-            if score < value {
-                pv.insert(0, position.clone());
+            if self.stopped {
+                break;
+            }
+            if score > value {
                 best = pv;
                 value = score;
             }
-            beta = beta.min(value);
-            if beta <= alpha {
-                break; // Alpha cut-off
+            alpha = alpha.max(value);
+            if alpha >= beta {
+                self.killers.store(ply, &position);
+                self.history.reward(&position, &game.side_to_play, depth);
+                self.cutoffs += 1;
+                break; // Beta cut-off
             }
         }
+        // A root that skipped moves has not found the best of them all.
+        if !self.stopped && (ply > 0 || self.excluded.is_empty()) {
+            let bound = if value <= original {
+                Bound::Upper
+            } else if value >= beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+            let mov = best.get(ply).cloned();
+            self.table.store(game.hash, depth, bound, value.into(), mov);
+        }
         (best, value)
     }
 }
 
+impl Thinker {
+    /// Deepens one ply at a time and returns the principal variation of the
+    /// deepest iteration to complete.
+    fn think(
+        &mut self,
+        game: &mut Game,
+        limit: u8,
+        start: Instant,
+        budget: Option<std::time::Duration>,
+        limits: &SearchLimits,
+    ) -> Vec<Position> {
+        self.deadline = limits.deadline(start, budget);
+        self.max_nodes = limits.nodes;
+        self.nodes = 0;
+        self.table_hits = 0;
+        self.cutoffs = 0;
+        self.reductions = 0;
+        self.stats = Stats::default();
+        self.stopped = false;
+        self.killers.clear();
+        self.history.age(game.size);
+        self.variations.clear();
+        let cache_hits = self.heuristic.hits();
+        let mut searched = 0;
+        for depth in 1..=limit {
+            let reductions = self.reductions;
+            let variations = self.vary(game, depth);
+            if self.stopped {
+                break;
+            }
+            let iteration = self.nodes - self.stats.nodes;
+            self.stats = Stats {
+                nodes: self.nodes,
+                table_hits: self.table_hits,
+                cache_hits: self.heuristic.hits() - cache_hits,
+                cutoffs: self.cutoffs,
+                branching: match searched {
+                    0 => 0.0,
+                    before => iteration as f64 / before as f64,
+                },
+                elapsed: start.elapsed(),
+            };
+            searched = iteration;
+            for (index, (pv, score)) in variations.iter().enumerate() {
+                let score = score.after(game.moves.len());
+                if self.multi_pv > 1 {
+                    println!(
+                        "info depth {} multipv {} score {} {} pv {:?}",
+                        depth,
+                        index + 1,
+                        score,
+                        self.stats,
+                        pv
+                    );
+                } else {
+                    println!(
+                        "info depth {} score {} {} pv {:?}",
+                        depth, score, self.stats, pv
+                    );
+                }
+            }
+            self.variations = variations;
+            // No deeper search finds a sooner win than one that every line
+            // of this depth was searched for, as long as no line of it was
+            // reduced to a shallower one that may hide it.
+            if let Some((_, Score::Win(moves))) = self.variations.first() {
+                let proven = self.reductions == reductions;
+                if proven && (*moves as usize).saturating_sub(game.moves.len()) <= depth as usize {
+                    break;
+                }
+            }
+            if let Some(budget) = budget {
+                // An iteration costs more than all the ones before it, so one
+                // that would not finish in time is not worth starting.
+                if start.elapsed() * 2 > budget {
+                    break;
+                }
+            }
+        }
+        match self.variations.first() {
+            Some((pv, _)) => pv.clone(),
+            // Not even the first iteration had the time or nodes to complete,
+            // so the move it searched first is the best guess there is.
+            None => self.first(game).into_iter().collect(),
+        }
+    }
+
+    /// The root move searched first, in the order `pvs` puts them in.
+    fn first(&mut self, game: &Game) -> Option<Position> {
+        let hint = self.table.probe(game.hash).and_then(|entry| entry.best);
+        let mut moves = self.looker.moves(game);
+        self.order(&mut moves, hint, 0, &game.side_to_play);
+        moves.into_iter().next()
+    }
+
+    /// Searches the root once per principal variation asked for, leaving out
+    /// the first moves of those already found each time.
+    fn vary(&mut self, game: &mut Game, depth: u8) -> Vec<(Vec<Position>, Score)> {
+        let mut variations = Vec::with_capacity(self.multi_pv);
+        while variations.len() < self.multi_pv {
+            let (pv, score) = self.pvs(game, game.moves.len(), depth, Score::MIN, Score::MAX);
+            if self.stopped || pv.is_empty() {
+                break;
+            }
+            self.excluded.push(pv[0].clone());
+            variations.push((pv, score));
+        }
+        self.excluded.clear();
+        variations.sort_by_key(|(_, score)| Reverse(*score));
+        variations
+    }
+
+    /// What the search had done by the end of the last completed iteration.
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// The principal variations of the last completed iteration, best first,
+    /// each with its score for the side to play.
+    pub fn variations(&self) -> &[(Vec<Position>, Score)] {
+        &self.variations
+    }
+
+    /// Sets how many of the best root moves to report and keep variations
+    /// for.
+    pub fn set_multi_pv(&mut self, count: usize) {
+        self.multi_pv = count.max(1);
+    }
+
+    /// Searches on a helper thread only to fill the shared table, starting
+    /// every other helper a ply deeper so that the threads drift apart.
+    fn assist(&mut self, game: &mut Game, limit: u8, index: usize) {
+        self.history.age(game.size);
+        for depth in (1 + (index % 2) as u8)..=limit {
+            self.pvs(game, game.moves.len(), depth, Score::MIN, Score::MAX);
+            if self.stopped {
+                break;
+            }
+        }
+    }
+}
+
+impl Thinker {
+    /// Stops thinking on the opponent's time and returns the hash of the game
+    /// that was being pondered, if any.
+    pub fn stop_pondering(&mut self) -> Option<u64> {
+        self.pondering.take().map(|pondering| pondering.hash)
+    }
+}
+
 impl Player for Thinker {
-    fn best(&mut self, game: &mut Game, _: Option<Time>) -> Result<Position> {
-        let (pv, _) = self.pvs(
-            game,
-            game.moves.len(),
-            self.2,
-            i64::MIN,
-            i64::MAX,
-            game.side_to_play == Side::X,
-        );
-        println!("info pv {:?}", pv);
-        match pv.first() {
+    fn best(
+        &mut self,
+        game: &mut Game,
+        time: Option<Time>,
+        limits: &SearchLimits,
+    ) -> Result<Position> {
+        // The clock runs from here, as waiting for the pondering threads and
+        // the VCF solver take from the same budget as the search.
+        let start = Instant::now();
+        // Anything learnt while pondering is in the table, whether or not the
+        // opponent made the expected move.
+        match self.stop_pondering() {
+            Some(hash) if hash == game.hash => println!("info ponder hit"),
+            Some(_) => println!("info ponder miss"),
+            None => {}
+        }
+        // The hash of a game does not tell how many in a row win, so what was
+        // stored under other rules would be taken for this game.
+        if self.rules != (game.size, game.win_length) {
+            self.rules = (game.size, game.win_length);
+            self.table.clear();
+        }
+        // Nor does it tell what a draw was worth, which changes with the
+        // contempt set and, for the engine's own, with the side it plays.
+        self.contempt.play_as(&game.side_to_play);
+        if self.draw != self.contempt.draw() {
+            self.draw = self.contempt.draw();
+            self.table.clear();
+        }
+        self.stats = Stats::default();
+        let full = self.skill.is_full();
+        let database = self.database.as_ref().filter(|_| full);
+        if let Some((outcome, position)) = database.and_then(|db| db.best(game)) {
+            println!(
+                "info database {:?} pv {:?}",
+                outcome,
+                vec![position.clone()]
+            );
+            let score = match outcome {
+                Outcome::Won => Score::MAX,
+                Outcome::Lost => Score::MIN,
+                _ => Score::Draw,
+            };
+            self.variations = vec![(vec![position.clone()], score)];
+            self.pv = vec![position.clone()];
+            return Ok(position);
+        }
+        // The solver takes from the time of the move like the search, and
+        // has nothing left to look with once it is up.
+        let budget = time.map(|time| time.budget(game));
+        let deadline = limits.deadline(start, budget);
+        let vcf = match full {
+            true => self.vcf.solve(game, deadline),
+            false => Solution::Unknown,
+        };
+        if let Solution::Won(sequence) = vcf {
+            println!("info vcf pv {:?}", sequence);
+            let score = Score::won(game.moves.len() + sequence.len());
+            self.variations = vec![(sequence.clone(), score)];
+            self.pv = sequence;
+            return Ok(self.pv[0].clone());
+        }
+        let limits = &self.skill.limit(limits);
+        // A level below full strength needs the scores of the moves it may
+        // play instead of the best.
+        let multi_pv = self.multi_pv;
+        self.multi_pv = multi_pv.max(self.skill.candidates());
+        let limit = match (budget, limits.nodes, limits.time, limits.depth) {
+            (None, None, None, None) => self.depth,
+            _ => game.playable.len().clamp(1, u8::MAX as usize) as u8,
+        };
+        let limit = limits.depth.map_or(limit, |depth| limit.min(depth.max(1)));
+        self.stop.store(false, Ordering::Relaxed);
+        self.table.age();
+        // A search stopped by counting nodes on one thread plays the same
+        // move every time as long as the clock leaves it the time, which
+        // helper threads would spoil.
+        let helpers = match (&self.factory, limits.nodes) {
+            (Some(_), None) => self.threads - 1,
+            _ => 0,
+        };
+        let best = thread::scope(|scope| {
+            for index in 0..helpers {
+                let factory = self.factory.clone().expect("parallel");
+                let table = self.table.clone();
+                let stop = self.stop.clone();
+                let depth = self.depth;
+                let contempt = self.contempt.clone();
+                let deadline = limits.deadline(start, budget);
+                let mut game = game.clone();
+                scope.spawn(move || {
+                    let (heuristic, looker) = factory();
+                    let mut helper = Thinker::build(heuristic, looker, depth, table, stop);
+                    helper.contempt = contempt;
+                    helper.deadline = deadline;
+                    helper.assist(&mut game, limit, index);
+                });
+            }
+            let best = self.think(game, limit, start, budget, limits);
+            self.stop.store(true, Ordering::Relaxed);
+            best
+        });
+        self.multi_pv = multi_pv;
+        self.pv = best;
+        let index = self
+            .skill
+            .choose(&self.variations, game.moves.len(), &mut self.rng);
+        if index > 0 {
+            let (pv, score) = self.variations[index].clone();
+            println!(
+                "info skill {} score {} pv {:?}",
+                self.skill.level(),
+                score.after(game.moves.len()),
+                pv
+            );
+            self.pv = pv;
+        }
+        match self.pv.first() {
             Some(position) => Ok(position.clone()),
             None => bail!("No moves found!"),
         }
     }
+
+    /// Searches the game after the best move and the reply expected from the
+    /// principal variation on background threads that fill the shared table,
+    /// until the next call to `best`.
+    fn ponder(&mut self, game: &Game) {
+        self.stop_pondering();
+        let factory = match &self.factory {
+            Some(factory) => factory.clone(),
+            None => return,
+        };
+        if self.pv.len() < 2 {
+            return;
+        }
+        let mut game = game.clone();
+        for position in &self.pv[..2] {
+            game.play(position);
+        }
+        if is_terminal(&game) {
+            return;
+        }
+        let stop = Arc::new(AtomicBool::new(false));
+        let limit = game.playable.len().clamp(1, u8::MAX as usize) as u8;
+        let threads = (0..self.threads)
+            .map(|index| {
+                let factory = factory.clone();
+                let table = self.table.clone();
+                let stop = stop.clone();
+                let depth = self.depth;
+                let contempt = self.contempt.clone();
+                let mut game = game.clone();
+                thread::spawn(move || {
+                    let (heuristic, looker) = factory();
+                    let mut helper = Thinker::build(heuristic, looker, depth, table, stop);
+                    helper.contempt = contempt;
+                    helper.assist(&mut game, limit, index);
+                })
+            })
+            .collect();
+        self.pondering = Some(Pondering {
+            hash: game.hash,
+            stop,
+            threads,
+        });
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        heuristics::{Assurer, Chance, Win},
-        lookers::{Nearby, Shuffler},
+        core::{set_seed, Duration, Seeded, SEED_LOCK},
+        hashers::Transposer,
+        heuristics::{Assurer, Cached, Chance, Null, Win},
+        lookers::{All, Nearby, Shuffler},
+        solvers::solve,
     };
 
     use super::*;
     use anyhow::Result;
-    use rand::thread_rng;
+    use pretty_assertions::assert_eq;
 
     #[test]
     fn test_thinker_returns_move() -> Result<()> {
@@ -112,12 +799,723 @@ mod tests {
         game.set_win_length(5);
         let mut thinker = Thinker::with_depth(
             Box::new(Win::new(Box::new(Assurer::new(Box::new(Chance))))),
-            Box::new(Shuffler::new(Box::new(Nearby::new(2)), thread_rng())),
+            Box::new(Shuffler::new(Box::new(Nearby::new(2)), Seeded::private(1))),
             2,
         );
-        let position = thinker.best(&mut game, None)?;
-        let expected = vec![Position(5, 9), Position(10, 4)];
+        let position = thinker.best(&mut game, None, &SearchLimits::default())?;
+        let expected = [Position(5, 9), Position(10, 4)];
         assert!(expected.contains(&position));
         Ok(())
     }
+
+    #[test]
+    fn test_thinker_honors_time_per_move() -> Result<()> {
+        let (_, mut game) =
+            Game::parse("15_/15_/15_/15_/15_/15_/6_x8_/7_o7_/15_/15_/15_/15_/15_/15_/15_ x")?;
+        game.set_win_length(5);
+        let mut thinker = Thinker::new(
+            Box::new(Win::new(Box::new(Assurer::new(Box::new(Chance))))),
+            Box::new(Nearby::new(1)),
+        );
+        let start = Instant::now();
+        let position = thinker.best(
+            &mut game,
+            Some(Time::PerMove(Duration::Milliseconds(300))),
+            &SearchLimits::default(),
+        )?;
+        assert!(start.elapsed() < std::time::Duration::from_millis(300));
+        assert!(game.playable.contains(&position));
+        Ok(())
+    }
+
+    #[test]
+    fn test_thinker_finds_win_with_time() -> Result<()> {
+        let (_, mut game) = Game::parse("x_o/_x_/o2_ x")?;
+        let mut thinker = Thinker::with_depth(
+            Box::new(Win::new(Box::new(Assurer::new(Box::new(Chance))))),
+            Box::new(Nearby::new(2)),
+            1,
+        );
+        let position = thinker.best(
+            &mut game,
+            Some(Time::PerMove(Duration::Milliseconds(500))),
+            &SearchLimits::default(),
+        )?;
+        assert_eq!(position, Position(2, 2));
+        Ok(())
+    }
+
+    fn negamax(thinker: &mut Thinker, game: &mut Game, depth: u8) -> Score {
+        if is_terminal(game) {
+            return thinker.evaluate(game);
+        }
+        if depth == 0 {
+            let visited = game.moves.len();
+            let (_, score) =
+                thinker.quiesce(game, visited, Score::MIN, Score::MAX, QUIESCENCE_DEPTH);
+            return score;
+        }
+        let mut value = Score::MIN;
+        for position in All.moves(game) {
+            game.play(&position);
+            value = value.max(-negamax(thinker, game, depth - 1));
+            game.undo();
+        }
+        value
+    }
+
+    #[test]
+    fn test_pvs_agrees_with_full_width_negamax() -> Result<()> {
+        for input in [
+            "3_/_x_/3_ o",
+            "x2_/_o_/3_ x",
+            "x_o/3_/3_ x",
+            "4_/_x2_/2_o_/4_ o",
+        ] {
+            let (_, mut game) = Game::parse(input)?;
+            game.set_win_length(3);
+            let mut thinker = Thinker::new(
+                Box::new(Win::new(Box::new(Chance))),
+                Box::new(Shuffler::new(Box::new(All), Seeded::private(2))),
+            );
+            thinker.selective = false;
+            let expected = negamax(&mut thinker, &mut game, 3);
+            let visited = game.moves.len();
+            let (_, actual) = thinker.pvs(&mut game, visited, 3, Score::MIN, Score::MAX);
+            assert_eq!(actual, expected, "{}", input);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_pvs_blocks_immediate_loss_for_o() -> Result<()> {
+        let (_, mut game) = Game::parse("xx_/_o_/3_ o")?;
+        let mut thinker = Thinker::with_depth(
+            Box::new(Win::new(Box::new(Chance))),
+            Box::new(Shuffler::new(Box::new(All), Seeded::private(3))),
+            3,
+        );
+        let position = thinker.best(&mut game, None, &SearchLimits::default())?;
+        assert_eq!(position, Position(0, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_best_leaves_root_move_in_table() -> Result<()> {
+        let (_, mut game) = Game::parse("x2_/_o_/3_ x")?;
+        let mut thinker = Thinker::with_depth(
+            Box::new(Win::new(Box::new(Chance))),
+            Box::new(Shuffler::new(Box::new(All), Seeded::private(4))),
+            3,
+        );
+        let position = thinker.best(&mut game, None, &SearchLimits::default())?;
+        let entry = thinker.table.probe(game.hash).expect("searched");
+        assert_eq!(entry.depth, 3);
+        assert_eq!(entry.best, Some(position));
+        Ok(())
+    }
+
+    #[test]
+    fn test_best_forgets_the_table_under_other_rules() -> Result<()> {
+        let (_, mut game) = Game::parse("x3_/_x2_/4_/o2_o o")?;
+        let mut thinker =
+            Thinker::with_depth(Box::new(Win::new(Box::new(Chance))), Box::new(All), 2);
+        game.set_win_length(3);
+        thinker.best(&mut game, None, &SearchLimits::default())?;
+        assert!(thinker.table.probe(game.hash).is_some());
+        game.set_win_length(4);
+        thinker.best(&mut game, None, &SearchLimits::default())?;
+        let entry = thinker.table.probe(game.hash).expect("searched");
+        assert!(!matches!(Score::from(entry.score), Score::Loss(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_best_forgets_the_table_when_contempt_changes() -> Result<()> {
+        let (_, mut game) = Game::parse("x_o/_._/o_x x")?;
+        let (_, mut other) = Game::parse("x_o/_._/o2_ o")?;
+        let contempt = Arc::new(Contempt::default());
+        let mut thinker = Thinker::new(
+            Box::new(Win::with_contempt(Box::new(Chance), contempt.clone())),
+            Box::new(All),
+        );
+        thinker.set_contempt(contempt.clone());
+        thinker.best(&mut game, None, &SearchLimits::default())?;
+        thinker.best(&mut other, None, &SearchLimits::default())?;
+        assert!(thinker.table.probe(game.hash).is_some());
+        contempt.set_engine(30);
+        thinker.best(&mut other, None, &SearchLimits::default())?;
+        assert!(thinker.table.probe(game.hash).is_none());
+        thinker.best(&mut game, None, &SearchLimits::default())?;
+        assert_eq!(thinker.variations()[0].1, Score::Value(-30));
+        Ok(())
+    }
+
+    #[test]
+    fn test_order_puts_hint_then_killers_then_history() -> Result<()> {
+        let mut thinker = Thinker::new(Box::new(Chance), Box::new(All));
+        thinker.history.age(3);
+        thinker.history.reward(&Position(2, 2), &Side::X, 2);
+        thinker.killers.store(1, &Position(0, 1));
+        let mut moves = vec![
+            Position(0, 0),
+            Position(0, 1),
+            Position(1, 1),
+            Position(2, 2),
+            Position(2, 0),
+        ];
+        thinker.order(&mut moves, Some(Position(2, 0)), 1, &Side::X);
+        let expected = vec![
+            Position(2, 0),
+            Position(0, 1),
+            Position(2, 2),
+            Position(0, 0),
+            Position(1, 1),
+        ];
+        assert_eq!(moves, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_best_plays_vcf_before_searching() -> Result<()> {
+        let (_, mut game) = Game::parse("9_/9_/4_o4_/9_/4_x4_/4_x4_/o3x5_/9_/9_ x")?;
+        game.set_win_length(5);
+        let mut thinker = Thinker::with_depth(Box::new(Chance), Box::new(Nearby::new(1)), 1);
+        let position = thinker.best(&mut game, None, &SearchLimits::default())?;
+        assert_eq!(position, Position(6, 4));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parallel_thinker_shares_table_with_helpers() -> Result<()> {
+        let (_, mut game) =
+            Game::parse("15_/15_/15_/15_/15_/15_/6_x8_/7_o7_/15_/15_/15_/15_/15_/15_/15_ x")?;
+        game.set_win_length(5);
+        let mut thinker = Thinker::parallel(
+            Arc::new(|| {
+                (
+                    Box::new(Win::new(Box::new(Assurer::new(Box::new(Chance))))),
+                    Box::new(Shuffler::new(Box::new(Nearby::new(1)), Seeded::private(5))),
+                )
+            }),
+            2,
+            4,
+        );
+        let position = thinker.best(
+            &mut game,
+            Some(Time::PerMove(Duration::Milliseconds(300))),
+            &SearchLimits::default(),
+        )?;
+        assert!(game.playable.contains(&position));
+        assert!(thinker.table.probe(game.hash).is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parallel_thinker_blocks_immediate_loss() -> Result<()> {
+        let (_, mut game) = Game::parse("xx_/_o_/3_ o")?;
+        let mut thinker = Thinker::parallel(
+            Arc::new(|| (Box::new(Win::new(Box::new(Chance))), Box::new(All))),
+            3,
+            3,
+        );
+        assert_eq!(
+            thinker.best(&mut game, None, &SearchLimits::default())?,
+            Position(0, 2)
+        );
+        Ok(())
+    }
+
+    fn pondering_thinker() -> Thinker {
+        Thinker::parallel(
+            Arc::new(|| {
+                (
+                    Box::new(Win::new(Box::new(Assurer::new(Box::new(Chance))))),
+                    Box::new(Nearby::new(1)),
+                )
+            }),
+            2,
+            2,
+        )
+    }
+
+    #[test]
+    fn test_ponder_searches_expected_reply() -> Result<()> {
+        let (_, mut game) =
+            Game::parse("15_/15_/15_/15_/15_/15_/6_x8_/7_o7_/15_/15_/15_/15_/15_/15_/15_ o")?;
+        game.set_win_length(5);
+        let mut thinker = pondering_thinker();
+        thinker.best(&mut game, None, &SearchLimits::default())?;
+        let expected = thinker.pv[..2].to_vec();
+        thinker.ponder(&game);
+        thread::sleep(std::time::Duration::from_millis(100));
+        for position in &expected {
+            game.play(position);
+        }
+        assert_eq!(thinker.stop_pondering(), Some(game.hash));
+        assert!(thinker.table.probe(game.hash).is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_best_stops_pondering_on_unexpected_reply() -> Result<()> {
+        let (_, mut game) =
+            Game::parse("15_/15_/15_/15_/15_/15_/6_x8_/7_o7_/15_/15_/15_/15_/15_/15_/15_ o")?;
+        game.set_win_length(5);
+        let mut thinker = pondering_thinker();
+        let position = thinker.best(&mut game, None, &SearchLimits::default())?;
+        thinker.ponder(&game);
+        game.play(&position);
+        let unexpected = game
+            .playable
+            .iter()
+            .find(|&x| Some(x) != thinker.pv.get(1))
+            .cloned()
+            .expect("playable");
+        game.play(&unexpected);
+        let position = thinker.best(&mut game, None, &SearchLimits::default())?;
+        assert!(thinker.pondering.is_none());
+        assert!(game.playable.contains(&position));
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_pv_keeps_distinct_root_moves_best_first() -> Result<()> {
+        let (_, mut game) = Game::parse("xx_/_o_/3_ o")?;
+        let mut thinker =
+            Thinker::with_depth(Box::new(Win::new(Box::new(Chance))), Box::new(All), 3);
+        thinker.set_multi_pv(3);
+        let position = thinker.best(&mut game, None, &SearchLimits::default())?;
+        let variations = thinker.variations();
+        assert_eq!(variations.len(), 3);
+        assert_eq!(variations[0].0[0], position);
+        assert_eq!(position, Position(0, 2));
+        assert_ne!(variations[1].0[0], variations[2].0[0]);
+        assert!(variations.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert!(variations[0].1 > variations[1].1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_pv_stops_when_out_of_moves() -> Result<()> {
+        let (_, mut game) = Game::parse("xox/xoo/o2_ x")?;
+        let mut thinker =
+            Thinker::with_depth(Box::new(Win::new(Box::new(Chance))), Box::new(All), 2);
+        thinker.set_multi_pv(5);
+        thinker.best(&mut game, None, &SearchLimits::default())?;
+        assert_eq!(thinker.variations().len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_quiesce_sees_win_past_horizon() -> Result<()> {
+        let (_, mut game) = Game::parse("oo_/_x_/2_x o")?;
+        let mut thinker = Thinker::new(Box::new(Win::new(Box::new(Null))), Box::new(All));
+        let (pv, score) = thinker.quiesce(&mut game, 4, Score::MIN, Score::MAX, QUIESCENCE_DEPTH);
+        assert_eq!(pv, vec![Position(0, 2)]);
+        assert_eq!(score, Score::Win(5));
+        Ok(())
+    }
+
+    #[test]
+    fn test_quiesce_follows_forced_blocks() -> Result<()> {
+        let (_, mut game) = Game::parse("oo_/_x_/2_x x")?;
+        let mut thinker = Thinker::new(Box::new(Win::new(Box::new(Null))), Box::new(All));
+        let (pv, _) = thinker.quiesce(&mut game, 4, Score::MIN, Score::MAX, QUIESCENCE_DEPTH);
+        assert_eq!(pv[0], Position(0, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_pvs_blocks_threat_beyond_horizon() -> Result<()> {
+        let (_, mut game) = Game::parse("oo_/_x_/2_x x")?;
+        let mut thinker = Thinker::new(
+            Box::new(Win::new(Box::new(Null))),
+            Box::new(Shuffler::new(Box::new(All), Seeded::private(6))),
+        );
+        let (pv, _) = thinker.pvs(&mut game, 4, 1, Score::MIN, Score::MAX);
+        assert_eq!(pv[0], Position(0, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_pvs_extends_threats_to_see_win() -> Result<()> {
+        let (_, mut game) = Game::parse("9_/9_/9_/6_2o_/6_2x_/6_2x_/6_2x_/3_o2x3_/o8_ x")?;
+        game.set_win_length(5);
+        let mut thinker =
+            Thinker::new(Box::new(Win::new(Box::new(Null))), Box::new(Nearby::new(1)));
+        let visited = game.moves.len();
+        let (pv, score) = thinker.pvs(&mut game, visited, 1, Score::MIN, Score::MAX);
+        assert_eq!(pv[..3], [Position(7, 6), Position(8, 6), Position(7, 7)]);
+        assert!(matches!(score, Score::Win(_)));
+        thinker.selective = false;
+        thinker.table = Arc::new(Table::new(16));
+        let (_, score) = thinker.pvs(&mut game, visited, 1, Score::MIN, Score::MAX);
+        assert_eq!(score, Score::Value(0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_win_found_past_reductions_keeps_deepening() -> Result<()> {
+        let (_, mut game) = Game::parse("7_/7_/7_/3_x3_/7_/7_/o6_ x")?;
+        game.set_win_length(3);
+        let mut thinker = Thinker::with_depth(
+            Box::new(Win::new(Box::new(Chance))),
+            Box::new(Nearby::new(1)),
+            5,
+        );
+        thinker.vcf = Vcf::new(0);
+        thinker.best(&mut game, None, &SearchLimits::default())?;
+        assert!(thinker.reductions > 0);
+        assert!(matches!(thinker.variations()[0].1, Score::Win(_)));
+        let entry = thinker.table.probe(game.hash).expect("searched");
+        assert_eq!(entry.depth, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_win_proven_without_reductions_stops_deepening() -> Result<()> {
+        let (_, mut game) = Game::parse("2.x_/.o_x/ox._/.o_. x")?;
+        game.set_win_length(3);
+        let mut thinker =
+            Thinker::with_depth(Box::new(Win::new(Box::new(Chance))), Box::new(All), 6);
+        thinker.vcf = Vcf::new(0);
+        thinker.best(&mut game, None, &SearchLimits::default())?;
+        assert!(thinker.reductions > 0);
+        assert!(matches!(thinker.variations()[0].1, Score::Win(_)));
+        let entry = thinker.table.probe(game.hash).expect("searched");
+        assert_eq!(entry.depth, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pvs_reductions_keep_the_score() -> Result<()> {
+        for _ in 0..5 {
+            let (_, mut game) = Game::parse("3_/3_/3_ x")?;
+            let mut selective = Thinker::new(
+                Box::new(Win::new(Box::new(Chance))),
+                Box::new(Shuffler::new(Box::new(All), Seeded::private(7))),
+            );
+            let mut exhaustive = Thinker::new(Box::new(Win::new(Box::new(Chance))), Box::new(All));
+            exhaustive.selective = false;
+            let visited = game.moves.len();
+            let (_, expected) = exhaustive.pvs(&mut game, visited, 9, Score::MIN, Score::MAX);
+            let (_, actual) = selective.pvs(&mut game, visited, 9, Score::MIN, Score::MAX);
+            assert_eq!(actual, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_symmetry_pruning_keeps_the_score_in_fewer_nodes() -> Result<()> {
+        let mut searches = vec![];
+        for symmetry in [
+            SymmetryPruning::Off,
+            SymmetryPruning::Root,
+            SymmetryPruning::Tree,
+        ] {
+            let (_, mut game) = Game::parse("3_/3_/3_ x")?;
+            let mut thinker = Thinker::new(Box::new(Win::new(Box::new(Chance))), Box::new(All));
+            thinker.set_symmetry_pruning(symmetry);
+            let (_, score) = thinker.pvs(&mut game, 0, 9, Score::MIN, Score::MAX);
+            searches.push((score, thinker.nodes));
+        }
+        assert_eq!(searches[0].0, searches[1].0);
+        assert_eq!(searches[0].0, searches[2].0);
+        assert!(searches[1].1 < searches[0].1);
+        assert!(searches[2].1 < searches[1].1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_pv_keeps_one_move_per_symmetric_class() -> Result<()> {
+        let (_, mut game) = Game::parse("3_/3_/3_ x")?;
+        let mut thinker =
+            Thinker::with_depth(Box::new(Win::new(Box::new(Chance))), Box::new(All), 2);
+        thinker.set_multi_pv(5);
+        thinker.best(&mut game, None, &SearchLimits::default())?;
+        assert_eq!(thinker.variations().len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pvs_stops_at_blocked_boards() -> Result<()> {
+        let (_, mut game) = Game::parse("x_o/_._/o_x x")?;
+        let mut thinker = Thinker::new(Box::new(Win::new(Box::new(Chance))), Box::new(All));
+        let (pv, score) = thinker.pvs(&mut game, 4, 9, Score::MIN, Score::MAX);
+        assert_eq!(score, Score::Draw);
+        assert_eq!(pv.len(), 1);
+        assert!(thinker.nodes <= 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stats_count_what_the_search_did() -> Result<()> {
+        let (_, mut game) = Game::parse("4_/4_/4_/4_ x")?;
+        game.set_win_length(3);
+        let mut thinker = Thinker::with_depth(
+            Box::new(Cached::new(
+                Box::new(Win::new(Box::new(Chance))),
+                Box::new(Transposer),
+            )),
+            Box::new(All),
+            4,
+        );
+        thinker.best(&mut game, None, &SearchLimits::default())?;
+        let stats = thinker.stats().clone();
+        assert_eq!(stats.nodes, thinker.nodes);
+        assert!(stats.table_hits > 0);
+        assert!(stats.cache_hits > 0);
+        assert!(stats.cutoffs > 0);
+        assert!(stats.branching > 0.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_contempt_scores_draws_for_the_side_played() -> Result<()> {
+        let (_, mut game) = Game::parse("x_o/_._/o_x x")?;
+        let contempt = Arc::new(Contempt::default());
+        contempt.set_engine(30);
+        let mut thinker = Thinker::new(
+            Box::new(Win::with_contempt(Box::new(Chance), contempt.clone())),
+            Box::new(All),
+        );
+        thinker.set_contempt(contempt);
+        thinker.best(&mut game, None, &SearchLimits::default())?;
+        assert_eq!(thinker.variations()[0].1, Score::Value(-30));
+        Ok(())
+    }
+
+    #[test]
+    fn test_pondering_scores_draws_by_contempt() -> Result<()> {
+        let (_, mut game) = Game::parse("3_/_x_/3_ o")?;
+        let contempt = Arc::new(Contempt::default());
+        contempt.set_engine(30);
+        let shared = contempt.clone();
+        let mut thinker = Thinker::parallel(
+            Arc::new(move || {
+                (
+                    Box::new(Win::with_contempt(Box::new(Chance), shared.clone())),
+                    Box::new(All),
+                )
+            }),
+            2,
+            1,
+        );
+        thinker.set_contempt(contempt);
+        thinker.best(&mut game, None, &SearchLimits::default())?;
+        thinker.ponder(&game);
+        let expected = thinker.pv[..2].to_vec();
+        while let Some(pondering) = &thinker.pondering {
+            if pondering.threads.iter().all(|thread| thread.is_finished()) {
+                break;
+            }
+            thread::sleep(std::time::Duration::from_millis(1));
+        }
+        for position in &expected {
+            game.play(position);
+        }
+        thinker.best(&mut game, None, &SearchLimits::default())?;
+        assert_eq!(thinker.variations()[0].1, Score::Value(-30));
+        Ok(())
+    }
+
+    #[test]
+    fn test_node_limit_is_reproducible() -> Result<()> {
+        let limits = SearchLimits {
+            nodes: Some(3_000),
+            ..SearchLimits::default()
+        };
+        let mut searches = vec![];
+        for _ in 0..2 {
+            let (_, mut game) =
+                Game::parse("15_/15_/15_/15_/15_/15_/6_x8_/7_o7_/15_/15_/15_/15_/15_/15_/15_ x")?;
+            game.set_win_length(5);
+            let mut thinker = pondering_thinker();
+            let position = thinker.best(&mut game, None, &limits)?;
+            searches.push((position, thinker.pv.clone(), thinker.nodes));
+        }
+        assert_eq!(searches[0], searches[1]);
+        assert!(searches[0].2 <= 3_001);
+        Ok(())
+    }
+
+    #[test]
+    fn test_same_seed_and_node_limit_play_the_same() -> Result<()> {
+        let _lock = SEED_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let limits = SearchLimits {
+            nodes: Some(2_000),
+            ..SearchLimits::default()
+        };
+        let mut searches = vec![];
+        for _ in 0..2 {
+            set_seed(11);
+            let (_, mut game) =
+                Game::parse("15_/15_/15_/15_/15_/15_/6_x8_/7_o7_/15_/15_/15_/15_/15_/15_/15_ x")?;
+            game.set_win_length(5);
+            let mut thinker = Thinker::new(
+                Box::new(Win::new(Box::new(Assurer::new(Box::new(Chance))))),
+                Box::new(Shuffler::new(
+                    Box::new(Nearby::new(1)),
+                    Seeded::with_stream(0),
+                )),
+            );
+            let position = thinker.best(&mut game, None, &limits)?;
+            searches.push((position, thinker.pv.clone()));
+        }
+        assert_eq!(searches[0], searches[1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_beginner_searches_little_but_takes_wins() -> Result<()> {
+        let (_, mut game) =
+            Game::parse("15_/15_/15_/15_/15_/15_/6_x8_/7_o7_/15_/15_/15_/15_/15_/15_/15_ x")?;
+        game.set_win_length(5);
+        let mut thinker = pondering_thinker();
+        thinker.set_skill(Skill::new(0));
+        let time = Some(Time::PerMove(Duration::Milliseconds(5_000)));
+        let position = thinker.best(&mut game, time, &SearchLimits::default())?;
+        assert!(game.playable.contains(&position));
+        assert!(thinker.nodes < 1_000);
+        let (_, mut game) = Game::parse("xx_/oo_/3_ x")?;
+        for _ in 0..10 {
+            let position = thinker.best(&mut game, None, &SearchLimits::default())?;
+            assert_eq!(position, Position(0, 2));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_weaker_level_keeps_to_the_clock() -> Result<()> {
+        let (_, mut game) =
+            Game::parse("15_/15_/15_/15_/15_/15_/6_x8_/7_o7_/15_/15_/15_/15_/15_/15_/15_ x")?;
+        game.set_win_length(5);
+        let mut thinker = pondering_thinker();
+        thinker.set_skill(Skill::new(19));
+        let start = Instant::now();
+        let time = Some(Time::PerMove(Duration::Milliseconds(100)));
+        let position = thinker.best(&mut game, time, &SearchLimits::default())?;
+        assert!(start.elapsed() < std::time::Duration::from_millis(1_000));
+        assert!(thinker.nodes < 102_400);
+        assert!(game.playable.contains(&position));
+        Ok(())
+    }
+
+    #[test]
+    fn test_depth_limit_stops_deepening() -> Result<()> {
+        let (_, mut game) =
+            Game::parse("15_/15_/15_/15_/15_/15_/6_x8_/7_o7_/15_/15_/15_/15_/15_/15_/15_ x")?;
+        game.set_win_length(5);
+        let mut thinker = pondering_thinker();
+        let limits = SearchLimits {
+            depth: Some(1),
+            ..SearchLimits::default()
+        };
+        let start = Instant::now();
+        let time = Some(Time::PerMove(Duration::Milliseconds(5_000)));
+        thinker.best(&mut game, time, &limits)?;
+        assert!(start.elapsed() < std::time::Duration::from_millis(2_500));
+        Ok(())
+    }
+
+    #[test]
+    fn test_node_limit_keeps_to_the_clock() -> Result<()> {
+        let (_, mut game) =
+            Game::parse("15_/15_/15_/15_/15_/15_/6_x8_/7_o7_/15_/15_/15_/15_/15_/15_/15_ x")?;
+        game.set_win_length(5);
+        let mut thinker = pondering_thinker();
+        let limits = SearchLimits {
+            nodes: Some(100_000_000),
+            ..SearchLimits::default()
+        };
+        let start = Instant::now();
+        let time = Some(Time::PerMove(Duration::Milliseconds(300)));
+        let position = thinker.best(&mut game, time, &limits)?;
+        assert!(start.elapsed() < std::time::Duration::from_millis(1_000));
+        assert!(game.playable.contains(&position));
+        Ok(())
+    }
+
+    #[test]
+    fn test_first_iteration_cut_short_plays_first_root_move() -> Result<()> {
+        let (_, mut game) =
+            Game::parse("15_/15_/15_/15_/15_/15_/6_x8_/7_o7_/15_/15_/15_/15_/15_/15_/15_ x")?;
+        game.set_win_length(5);
+        let mut thinker = pondering_thinker();
+        let limits = SearchLimits {
+            nodes: Some(1),
+            ..SearchLimits::default()
+        };
+        let position = thinker.best(&mut game, None, &limits)?;
+        assert!(thinker.variations().is_empty());
+        assert_eq!(Some(position), thinker.first(&game));
+        Ok(())
+    }
+
+    #[test]
+    fn test_depth_limit_alone_sets_the_depth() -> Result<()> {
+        let (_, mut game) = Game::parse("x2_/_o_/3_ x")?;
+        let mut thinker =
+            Thinker::with_depth(Box::new(Win::new(Box::new(Chance))), Box::new(All), 2);
+        let limits = SearchLimits {
+            depth: Some(4),
+            ..SearchLimits::default()
+        };
+        thinker.best(&mut game, None, &limits)?;
+        let entry = thinker.table.probe(game.hash).expect("searched");
+        assert_eq!(entry.depth, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hard_time_limit_cuts_search_short() -> Result<()> {
+        let (_, mut game) =
+            Game::parse("15_/15_/15_/15_/15_/15_/6_x8_/7_o7_/15_/15_/15_/15_/15_/15_/15_ x")?;
+        game.set_win_length(5);
+        let mut thinker = pondering_thinker();
+        let limits = SearchLimits {
+            time: Some(std::time::Duration::from_millis(200)),
+            ..SearchLimits::default()
+        };
+        let start = Instant::now();
+        let position = thinker.best(&mut game, None, &limits)?;
+        assert!(game.playable.contains(&position));
+        assert!(start.elapsed() < std::time::Duration::from_millis(1_500));
+        Ok(())
+    }
+
+    #[test]
+    fn test_thinker_plays_moves_the_solver_finds_optimal() -> Result<()> {
+        for input in [
+            "3_/3_/3_ x",
+            "3_/_x_/3_ o",
+            "x2_/_o_/3_ x",
+            "xx_/_o_/3_ o",
+            "x_o/_x_/o2_ x",
+            "_x_/xo_/3_ o",
+        ] {
+            let (_, mut game) = Game::parse(input)?;
+            let (_, optimal) = solve(&game);
+            let mut thinker =
+                Thinker::with_depth(Box::new(Win::new(Box::new(Chance))), Box::new(All), 4);
+            let position = thinker.best(&mut game, None, &SearchLimits::default())?;
+            assert!(optimal.contains(&position), "{} {}", input, position);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_plays_from_database_before_searching() -> Result<()> {
+        let (_, empty) = Game::parse("3_/3_/3_ x")?;
+        let (_, mut game) = Game::parse("x2_/_o_/3_ x")?;
+        let mut thinker = Thinker::with_depth(Box::new(Null), Box::new(All), 1);
+        thinker.set_database(Arc::new(Database::generate(&empty)));
+        let position = thinker.best(&mut game, None, &SearchLimits::default())?;
+        assert_eq!(thinker.nodes, 0);
+        assert!(solve(&game).1.contains(&position));
+        Ok(())
+    }
 }
@@ -0,0 +1,8 @@
+/// How a stored score relates to the true one, which a search cut off by its
+/// window only knows from one side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
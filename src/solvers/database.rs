@@ -0,0 +1,275 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use anyhow::{bail, Result};
+use rustc_hash::FxHashSet;
+
+use crate::{
+    core::{Game, Position},
+    hashers::Transposer,
+    heuristics::termination::Termination,
+};
+
+use super::{Outcome, Perfect};
+
+const MAGIC: &[u8; 4] = b"R3DB";
+const VERSION: u8 = 1;
+
+/// Solved positions for one board size and win length, stored on disk as a
+/// header followed by the canonical hash and value of every position, sorted
+/// by hash, in nine bytes each.
+#[derive(Debug, PartialEq)]
+pub struct Database {
+    size: u8,
+    win_length: u8,
+    entries: Vec<(u64, i8)>,
+}
+
+impl Database {
+    /// Solves every position reachable from the game.
+    pub fn generate(game: &Game) -> Self {
+        let mut perfect = Perfect::exhaustive();
+        let mut seen = FxHashSet::default();
+        settle(&mut perfect, &mut game.clone(), &mut seen);
+        Self::from(&perfect)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION, self.size, self.win_length])?;
+        writer.write_all(&(self.entries.len() as u64).to_le_bytes())?;
+        for (hash, value) in &self.entries {
+            writer.write_all(&hash.to_le_bytes())?;
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self> {
+        let mut header = [0u8; 15];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            bail!("not a solution database");
+        }
+        if header[4] != VERSION {
+            bail!("unsupported solution database version {}", header[4]);
+        }
+        let count = u64::from_le_bytes(header[7..].try_into()?);
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        if count.checked_mul(9) != Some(bytes.len() as u64) {
+            bail!(
+                "solution database does not hold the {} entries it claims",
+                count
+            );
+        }
+        let mut entries = Vec::with_capacity(bytes.len() / 9);
+        for entry in bytes.chunks_exact(9) {
+            let hash = u64::from_le_bytes(entry[..8].try_into()?);
+            entries.push((hash, entry[8] as i8));
+        }
+        if !entries.windows(2).all(|pair| pair[0].0 < pair[1].0) {
+            bail!("solution database is not sorted");
+        }
+        Ok(Self {
+            size: header[5],
+            win_length: header[6],
+            entries,
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::read(&mut BufReader::new(File::open(path)?))
+    }
+
+    /// What the side to play can force, if the game is in the database.
+    pub fn probe(&self, game: &Game) -> Option<Outcome> {
+        if (game.size, game.win_length) != (self.size, self.win_length) {
+            return None;
+        }
+        if let Some(termination) = Termination::of(game) {
+            return Some(match termination {
+                Termination::Drawn => Outcome::Drawn,
+                Termination::Won(_) => Outcome::Lost,
+            });
+        }
+        let (hash, _) = Transposer.canonical(game);
+        self.entries
+            .binary_search_by_key(&hash, |(hash, _)| *hash)
+            .ok()
+            .map(|index| Outcome::from(self.entries[index].1))
+    }
+
+    /// What the side to play can force and a move that forces it, if the game
+    /// and enough of the games after it are in the database.
+    pub fn best(&self, game: &mut Game) -> Option<(Outcome, Position)> {
+        let outcome = self.probe(game)?;
+        let reply = match outcome {
+            Outcome::Won => Outcome::Lost,
+            Outcome::Lost => Outcome::Won,
+            ref other => other.clone(),
+        };
+        let mut moves: Vec<Position> = game.playable.iter().cloned().collect();
+        moves.sort();
+        moves.into_iter().find_map(|position| {
+            game.play(&position);
+            let found = self.probe(game) == Some(reply.clone());
+            game.undo();
+            found.then(|| (outcome.clone(), position))
+        })
+    }
+}
+
+/// Settles the game and every game reachable from it, once per position up to
+/// symmetry. The solver only looks at the moves it needs to tell the value of
+/// the game, which leaves out the ones after a blunder, so those are walked
+/// here.
+fn settle(perfect: &mut Perfect, game: &mut Game, seen: &mut FxHashSet<u64>) {
+    if Termination::of(game).is_some() {
+        return;
+    }
+    let (hash, _) = Transposer.canonical(game);
+    if !seen.insert(hash) {
+        return;
+    }
+    perfect.value(game);
+    let moves: Vec<Position> = game.playable.iter().cloned().collect();
+    for position in moves {
+        game.play(&position);
+        settle(perfect, game, seen);
+        game.undo();
+    }
+}
+
+impl From<&Perfect> for Database {
+    fn from(perfect: &Perfect) -> Self {
+        let (size, win_length) = perfect.rules();
+        let mut entries: Vec<(u64, i8)> = perfect.settled().collect();
+        entries.sort();
+        Self {
+            size,
+            win_length,
+            entries,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solvers::solve;
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    fn three_by_three() -> Result<Database> {
+        let (_, game) = Game::parse("3_/3_/3_ x")?;
+        Ok(Database::generate(&game))
+    }
+
+    #[test]
+    fn test_round_trips_through_bytes() -> Result<()> {
+        let database = three_by_three()?;
+        let mut bytes = vec![];
+        database.write(&mut bytes)?;
+        assert_eq!(bytes.len(), 15 + 9 * database.len());
+        assert_eq!(Database::read(&mut bytes.as_slice())?, database);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_other_files() {
+        assert!(Database::read(&mut b"not a database at all".as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_rejects_more_entries_than_the_file_holds() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend([VERSION, 3, 3]);
+        bytes.extend(u64::MAX.to_le_bytes());
+        bytes.extend([0u8; 9]);
+        assert!(Database::read(&mut bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_probes_every_reachable_position() -> Result<()> {
+        let database = three_by_three()?;
+        let (_, empty) = Game::parse("3_/3_/3_ x")?;
+        let (_, turned) = Game::parse("2_x/3_/o2_ x")?;
+        let (_, lost) = Game::parse("x_x/ox_/o2_ o")?;
+        let (_, blundered) = Game::parse("xx_/_o_/2_o x")?;
+        assert_eq!(database.probe(&empty), Some(Outcome::Drawn));
+        assert_eq!(database.probe(&blundered), Some(Outcome::Won));
+        assert_eq!(database.probe(&turned), Some(solve(&turned).0));
+        assert_eq!(database.probe(&lost), Some(Outcome::Lost));
+        Ok(())
+    }
+
+    fn unprobed(database: &Database, game: &mut Game, seen: &mut FxHashSet<u64>) -> usize {
+        if !seen.insert(game.hash) {
+            return 0;
+        }
+        if database.probe(game).is_none() {
+            return 1;
+        }
+        if Termination::of(game).is_some() {
+            return 0;
+        }
+        let moves: Vec<Position> = game.playable.iter().cloned().collect();
+        moves
+            .iter()
+            .map(|position| {
+                game.play(position);
+                let count = unprobed(database, game, seen);
+                game.undo();
+                count
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_leaves_out_no_reachable_position() -> Result<()> {
+        let database = three_by_three()?;
+        let (_, mut game) = Game::parse("3_/3_/3_ x")?;
+        let mut seen = FxHashSet::default();
+        assert_eq!(unprobed(&database, &mut game, &mut seen), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_best_keeps_the_value() -> Result<()> {
+        let database = three_by_three()?;
+        let (_, mut game) = Game::parse("xx_/_o_/3_ o")?;
+        assert_eq!(
+            database.best(&mut game),
+            Some((Outcome::Drawn, Position(0, 2)))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_does_not_answer_for_other_rules() -> Result<()> {
+        let database = three_by_three()?;
+        let (_, mut game) = Game::parse("4_/4_/4_/4_ x")?;
+        game.set_win_length(3);
+        assert_eq!(database.probe(&game), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,15 @@
+pub mod bound;
+pub mod database;
+pub mod outcome;
+pub mod perfect;
+pub mod solution;
+pub mod vcf;
+pub mod vct;
+
+pub use bound::*;
+pub use database::*;
+pub use outcome::*;
+pub use perfect::*;
+pub use solution::*;
+pub use vcf::*;
+pub use vct::*;
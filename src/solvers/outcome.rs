@@ -0,0 +1,20 @@
+/// What the side to play can force, as far as a solver could tell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Won,
+    Drawn,
+    Lost,
+    Unknown,
+}
+
+/// Reads a value for the side to play, one for a win, zero for a draw and
+/// minus one for a loss.
+impl From<i8> for Outcome {
+    fn from(value: i8) -> Self {
+        match value {
+            1 => Outcome::Won,
+            0 => Outcome::Drawn,
+            _ => Outcome::Lost,
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use rustc_hash::FxHashMap;
+
+use crate::{
+    core::{Game, Position},
+    hashers::Transposer,
+    heuristics::{termination::Termination, threats::winning_cells},
+};
+
+use super::{Bound, Outcome};
+
+/// Solves small games outright, like 3x3, 4x4 or 5x5 with four in a row, by
+/// searching every move for a win, draw or loss and keeping each position it
+/// settles once for all its turns and flips. The empty 5x5 board with four in
+/// a row takes minutes and millions of positions.
+pub struct Perfect {
+    rules: (u8, u8),
+    exhaustive: bool,
+    table: FxHashMap<u64, (Bound, i8)>,
+}
+
+impl Default for Perfect {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Perfect {
+    pub fn new() -> Self {
+        Self {
+            rules: (0, 0),
+            exhaustive: false,
+            table: FxHashMap::default(),
+        }
+    }
+
+    /// Settles the exact value of every position it visits instead of just
+    /// enough of them to tell the value at the root, for a solution database.
+    pub fn exhaustive() -> Self {
+        Self {
+            exhaustive: true,
+            ..Self::new()
+        }
+    }
+
+    pub fn rules(&self) -> (u8, u8) {
+        self.rules
+    }
+
+    /// The canonical hashes of the positions whose exact value is known,
+    /// with that value for the side to play.
+    pub fn settled(&self) -> impl Iterator<Item = (u64, i8)> + '_ {
+        self.table
+            .iter()
+            .filter(|(_, (bound, value))| match bound {
+                Bound::Exact => true,
+                Bound::Lower => *value == 1,
+                Bound::Upper => *value == -1,
+            })
+            .map(|(&hash, &(_, value))| (hash, value))
+    }
+
+    /// Positions settled so far, up to symmetry.
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Forgets what was settled under other rules, as the hash of a game does
+    /// not tell how many in a row win.
+    fn prepare(&mut self, game: &Game) {
+        if self.rules != (game.size, game.win_length) {
+            self.rules = (game.size, game.win_length);
+            self.table.clear();
+        }
+    }
+
+    /// What the side to play can force against any defence.
+    pub fn value(&mut self, game: &mut Game) -> Outcome {
+        self.prepare(game);
+        Outcome::from(self.search(game, -1, 1))
+    }
+
+    /// What the side to play can force, and every move that forces it.
+    pub fn solve(&mut self, game: &mut Game) -> (Outcome, Vec<Position>) {
+        self.prepare(game);
+        let mut best = -1;
+        let mut optimal = vec![];
+        for position in ordered(game) {
+            game.play(&position);
+            let value = -self.search(game, -1, 1);
+            game.undo();
+            if value > best || optimal.is_empty() {
+                best = value;
+                optimal.clear();
+            }
+            if value == best {
+                optimal.push(position);
+            }
+        }
+        match Termination::of(game) {
+            Some(Termination::Drawn) => (Outcome::Drawn, vec![]),
+            Some(Termination::Won(_)) => (Outcome::Lost, vec![]),
+            None => (Outcome::from(best), optimal),
+        }
+    }
+
+    /// Scores the game for the side to play, one for a win, zero for a draw
+    /// and minus one for a loss, exactly when it falls inside the window and
+    /// as a bound on the right side of it otherwise.
+    fn search(&mut self, game: &mut Game, mut alpha: i8, mut beta: i8) -> i8 {
+        match Termination::of(game) {
+            Some(Termination::Drawn) => return 0,
+            Some(Termination::Won(_)) => return -1,
+            None => {}
+        }
+        let (hash, _) = Transposer.canonical(game);
+        if let Some(&(bound, value)) = self.table.get(&hash) {
+            match bound {
+                Bound::Exact => return value,
+                Bound::Lower => alpha = alpha.max(value),
+                Bound::Upper => beta = beta.min(value),
+            }
+            if alpha >= beta {
+                return value;
+            }
+        }
+        let side = game.side_to_play.clone();
+        if !winning_cells(game, &side).is_empty() {
+            self.table.insert(hash, (Bound::Exact, 1));
+            return 1;
+        }
+        // Only a block can stop an immediate loss, and nothing stops two.
+        let blocks = winning_cells(game, &side.other());
+        let moves = match blocks.len() {
+            0 => ordered(game),
+            1 => blocks,
+            _ => {
+                self.table.insert(hash, (Bound::Exact, -1));
+                return -1;
+            }
+        };
+        let original = (alpha, beta);
+        let mut value = -1;
+        for position in moves {
+            game.play(&position);
+            let score = match self.exhaustive {
+                true => -self.search(game, -1, 1),
+                false => -self.search(game, -beta, -alpha),
+            };
+            game.undo();
+            value = value.max(score);
+            alpha = alpha.max(value);
+            if alpha >= beta || value == 1 {
+                break;
+            }
+        }
+        let bound = if value <= original.0 {
+            Bound::Upper
+        } else if value >= original.1 {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.insert(hash, (bound, value));
+        value
+    }
+}
+
+/// The playable cells from the centre out, where lines tend to meet.
+fn ordered(game: &Game) -> Vec<Position> {
+    let centre = game.size as i16 - 1;
+    let mut moves: Vec<Position> = game.playable.iter().cloned().collect();
+    moves.sort_by_key(|Position(i, j)| {
+        let distance = (2 * *i as i16 - centre).abs() + (2 * *j as i16 - centre).abs();
+        (distance, *i, *j)
+    });
+    moves
+}
+
+/// Solves the game from scratch, for what the side to play can force and
+/// every move that forces it.
+pub fn solve(game: &Game) -> (Outcome, Vec<Position>) {
+    Perfect::new().solve(&mut game.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::Side,
+        heuristics::{Chance, Heuristic, Score},
+    };
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_empty_board_is_drawn_whatever_the_first_move() -> Result<()> {
+        let (_, game) = Game::parse("3_/3_/3_ x")?;
+        let (outcome, moves) = solve(&game);
+        assert_eq!(outcome, Outcome::Drawn);
+        assert_eq!(moves.len(), 9);
+        Ok(())
+    }
+
+    #[test]
+    fn test_finds_only_winning_move() -> Result<()> {
+        let (_, game) = Game::parse("xx_/oo_/3_ x")?;
+        assert_eq!(solve(&game), (Outcome::Won, vec![Position(0, 2)]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_finds_every_winning_move() -> Result<()> {
+        let (_, game) = Game::parse("x_o/_x_/o2_ x")?;
+        let (outcome, mut moves) = solve(&game);
+        moves.sort();
+        assert_eq!(outcome, Outcome::Won);
+        assert_eq!(
+            moves,
+            vec![
+                Position(0, 1),
+                Position(1, 0),
+                Position(1, 2),
+                Position(2, 1),
+                Position(2, 2)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_loses_to_double_threat() -> Result<()> {
+        let (_, game) = Game::parse("x_x/ox_/o2_ o")?;
+        let (outcome, _) = solve(&game);
+        assert_eq!(outcome, Outcome::Lost);
+        Ok(())
+    }
+
+    #[test]
+    fn test_first_player_wins_three_in_a_row_on_four_by_four() -> Result<()> {
+        let (_, mut game) = Game::parse("4_/4_/4_/4_ x")?;
+        game.set_win_length(3);
+        assert_eq!(Perfect::new().value(&mut game), Outcome::Won);
+        Ok(())
+    }
+
+    #[test]
+    fn test_four_in_a_row_on_four_by_four_is_drawn() -> Result<()> {
+        let (_, mut game) = Game::parse("4_/4_/4_/4_ x")?;
+        assert_eq!(Perfect::new().value(&mut game), Outcome::Drawn);
+        Ok(())
+    }
+
+    #[test]
+    fn test_forgets_positions_under_other_rules() -> Result<()> {
+        let (_, mut game) = Game::parse("x3_/_x2_/4_/o2_o o")?;
+        let mut perfect = Perfect::new();
+        game.set_win_length(3);
+        assert_eq!(perfect.value(&mut game), Outcome::Lost);
+        game.set_win_length(4);
+        assert_ne!(perfect.value(&mut game), Outcome::Lost);
+        Ok(())
+    }
+
+    #[test]
+    fn test_chance_only_calls_wins_the_solver_agrees_with() -> Result<()> {
+        for input in [
+            "x2_/_o_/3_ x",
+            "xx_/_o_/3_ o",
+            "x_o/_x_/o2_ x",
+            "x_x/ox_/o2_ o",
+            "_x_/xo_/3_ o",
+        ] {
+            let (_, mut game) = Game::parse(input)?;
+            let mut perfect = Perfect::new();
+            let outcome = perfect.value(&mut game);
+            let winner = match Chance.score(&game) {
+                Score::Win(_) => Side::X,
+                Score::Loss(_) => Side::O,
+                _ => continue,
+            };
+            let expected = if winner == game.side_to_play {
+                Outcome::Won
+            } else {
+                Outcome::Lost
+            };
+            assert_eq!(outcome, expected, "{}", input);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+use crate::core::Position;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Solution {
+    /// The side to play wins by playing out the sequence, whatever the
+    /// defence, alternating attacker and defender moves.
+    Won(Vec<Position>),
+    /// The search was exhaustive and found no forced win.
+    Refuted,
+    /// The search ran out of budget before it could decide.
+    Unknown,
+}
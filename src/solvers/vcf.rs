@@ -0,0 +1,267 @@
+use std::time::Instant;
+
+use rustc_hash::FxHashSet;
+
+use crate::{
+    core::{Game, Position},
+    heuristics::threats::{threats, winning_cells},
+};
+
+use super::Solution;
+
+/// What makes a search by continuous threats: the threats the attacker may
+/// make, and the replies that may stop one that is not already a four.
+pub(crate) trait Forcing {
+    /// The moves that make a threat for the side to play.
+    fn threats(game: &Game) -> Vec<Position>;
+
+    /// The replies to a threat that leaves the attacker no move from a win,
+    /// none when it was no threat at all.
+    fn replies(game: &Game) -> Vec<Position>;
+}
+
+/// A win where every attacking move makes a threat the defender has to
+/// answer, given up on past a depth, a node budget or a deadline.
+pub(crate) struct Continuous {
+    budget: usize,
+    nodes: usize,
+    deadline: Option<Instant>,
+    refuted: FxHashSet<u64>,
+}
+
+impl Continuous {
+    pub(crate) fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            nodes: 0,
+            deadline: None,
+            refuted: FxHashSet::default(),
+        }
+    }
+
+    pub(crate) fn solve<F: Forcing>(
+        &mut self,
+        game: &mut Game,
+        depth: u8,
+        deadline: Option<Instant>,
+    ) -> Solution {
+        self.nodes = 0;
+        self.deadline = deadline;
+        self.refuted.clear();
+        self.attack::<F>(game, depth)
+    }
+
+    fn attack<F: Forcing>(&mut self, game: &mut Game, depth: u8) -> Solution {
+        self.nodes += 1;
+        if self.nodes > self.budget
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Solution::Unknown;
+        }
+        let attacker = game.side_to_play.clone();
+        let defender = attacker.other();
+        if let Some(position) = winning_cells(game, &attacker).first() {
+            return Solution::Won(vec![position.clone()]);
+        }
+        if self.refuted.contains(&game.hash) {
+            return Solution::Refuted;
+        }
+        let blocks = winning_cells(game, &defender);
+        if blocks.len() > 1 {
+            self.refuted.insert(game.hash);
+            return Solution::Refuted;
+        }
+        if depth == 0 {
+            return Solution::Unknown;
+        }
+        let mut unknown = false;
+        for position in F::threats(game) {
+            // A threat elsewhere is too slow when the defender is about to win.
+            if !blocks.is_empty() && blocks[0] != position {
+                continue;
+            }
+            game.play(&position);
+            let solution = self.defend::<F>(game, depth - 1);
+            game.undo();
+            match solution {
+                Solution::Won(mut sequence) => {
+                    sequence.insert(0, position);
+                    return Solution::Won(sequence);
+                }
+                Solution::Unknown => unknown = true,
+                Solution::Refuted => {}
+            }
+        }
+        if unknown {
+            return Solution::Unknown;
+        }
+        self.refuted.insert(game.hash);
+        Solution::Refuted
+    }
+
+    /// Tries every reply that could stop the threat just made, and only
+    /// calls it a win when the attacker wins against all of them.
+    fn defend<F: Forcing>(&mut self, game: &mut Game, depth: u8) -> Solution {
+        let defender = game.side_to_play.clone();
+        let attacker = defender.other();
+        if !winning_cells(game, &defender).is_empty() {
+            return Solution::Refuted;
+        }
+        let wins = winning_cells(game, &attacker);
+        if wins.len() > 1 {
+            return Solution::Won(vec![wins[0].clone(), wins[1].clone()]);
+        }
+        let defences = match wins.first() {
+            Some(position) => vec![position.clone()],
+            None => F::replies(game),
+        };
+        if defences.is_empty() {
+            return Solution::Refuted;
+        }
+        let mut longest: Vec<Position> = vec![];
+        for position in defences {
+            game.play(&position);
+            let solution = self.attack::<F>(game, depth);
+            game.undo();
+            match solution {
+                Solution::Won(mut sequence) => {
+                    if sequence.len() + 1 > longest.len() {
+                        sequence.insert(0, position);
+                        longest = sequence;
+                    }
+                }
+                solution => return solution,
+            }
+        }
+        Solution::Won(longest)
+    }
+}
+
+/// Looks for a victory by continuous fours: a win where every attacking move
+/// threatens to win right away, so the defender can only block.
+pub struct Vcf(Continuous);
+
+impl Vcf {
+    pub fn new(budget: usize) -> Self {
+        Self(Continuous::new(budget))
+    }
+
+    /// Gives up once it runs out of its node budget or of time, whichever
+    /// comes first.
+    pub fn solve(&mut self, game: &mut Game, deadline: Option<Instant>) -> Solution {
+        // Every four fills a cell and takes another to block, so the fours
+        // run out long before the depth would.
+        self.0.solve::<Vcf>(game, u8::MAX, deadline)
+    }
+
+    /// The moves that leave the side to play one move away from a win.
+    pub(crate) fn fours(game: &Game) -> Vec<Position> {
+        let mut fours: Vec<Position> = threats(game, &game.side_to_play, 2)
+            .into_iter()
+            .flatten()
+            .collect();
+        fours.sort();
+        fours.dedup();
+        fours
+    }
+}
+
+impl Forcing for Vcf {
+    fn threats(game: &Game) -> Vec<Position> {
+        Vcf::fours(game)
+    }
+
+    /// A move that is no four threatens nothing the defender has to answer.
+    fn replies(_: &Game) -> Vec<Position> {
+        vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{core::Side, heuristics::termination::Termination};
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    fn replay(game: &Game, sequence: &[Position]) -> Option<Termination> {
+        let mut game = game.clone();
+        for position in sequence {
+            game.play(position);
+        }
+        Termination::of(&game)
+    }
+
+    #[test]
+    fn test_vcf_takes_immediate_win() -> Result<()> {
+        let (_, mut game) = Game::parse("xx_/_o_/o2_ x")?;
+        let solution = Vcf::new(100).solve(&mut game, None);
+        assert_eq!(solution, Solution::Won(vec![Position(0, 2)]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_vcf_finds_double_four() -> Result<()> {
+        let (_, mut game) = Game::parse("9_/4_o4_/4_x4_/4_x4_/4_x4_/o3x_4_/9_/9_/9_ x")?;
+        game.set_win_length(5);
+        match Vcf::new(1000).solve(&mut game, None) {
+            Solution::Won(sequence) => {
+                assert_eq!(sequence[0], Position(5, 4));
+                assert_eq!(sequence.len(), 3);
+                assert_eq!(replay(&game, &sequence), Some(Termination::Won(Side::X)));
+            }
+            solution => panic!("expected a win, got {:?}", solution),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_vcf_finds_win_through_forced_block() -> Result<()> {
+        let (_, mut game) = Game::parse("9_/9_/4_o4_/9_/4_x4_/4_x4_/o3x5_/9_/9_ x")?;
+        game.set_win_length(5);
+        match Vcf::new(1000).solve(&mut game, None) {
+            Solution::Won(sequence) => {
+                assert_eq!(sequence[..2], [Position(6, 4), Position(6, 5)]);
+                assert_eq!(replay(&game, &sequence), Some(Termination::Won(Side::X)));
+            }
+            solution => panic!("expected a win, got {:?}", solution),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_vcf_refutes_when_no_fours() -> Result<()> {
+        let (_, mut game) = Game::parse("9_/9_/9_/9_/4_x4_/4_o4_/9_/9_/9_ x")?;
+        game.set_win_length(5);
+        assert_eq!(Vcf::new(1000).solve(&mut game, None), Solution::Refuted);
+        Ok(())
+    }
+
+    #[test]
+    fn test_vcf_refutes_when_defender_wins_first() -> Result<()> {
+        let (_, mut game) = Game::parse("9_/4_o4_/4_x4_/4_x4_/4_x4_/o3x_4_/9_/9_/_4o4_ x")?;
+        game.set_win_length(5);
+        let solution = Vcf::new(1000).solve(&mut game, None);
+        assert_eq!(solution, Solution::Refuted);
+        Ok(())
+    }
+
+    #[test]
+    fn test_vcf_is_unknown_when_out_of_budget() -> Result<()> {
+        let (_, mut game) = Game::parse("9_/9_/4_o4_/9_/4_x4_/4_x4_/o3x5_/9_/9_ x")?;
+        game.set_win_length(5);
+        assert_eq!(Vcf::new(1).solve(&mut game, None), Solution::Unknown);
+        Ok(())
+    }
+
+    #[test]
+    fn test_vcf_is_unknown_past_the_deadline() -> Result<()> {
+        let (_, mut game) = Game::parse("9_/9_/4_o4_/9_/4_x4_/4_x4_/o3x5_/9_/9_ x")?;
+        game.set_win_length(5);
+        let solution = Vcf::new(1000).solve(&mut game, Some(Instant::now()));
+        assert_eq!(solution, Solution::Unknown);
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+use std::time::Instant;
+
+use crate::{
+    core::{Cell, Game, Position},
+    heuristics::threats::{open, threats},
+};
+
+use super::{
+    vcf::{Continuous, Forcing},
+    Solution, Vcf,
+};
+
+/// Looks for a victory by continuous threats: a win where every attacking
+/// move either threatens to win right away or makes an open three, a line
+/// one move short of an open four that can no longer be stopped.
+pub struct Vct {
+    search: Continuous,
+    depth: u8,
+}
+
+impl Vct {
+    pub fn new(budget: usize, depth: u8) -> Self {
+        Self {
+            search: Continuous::new(budget),
+            depth,
+        }
+    }
+
+    /// Gives up past its depth, or once it runs out of its node budget or of
+    /// time.
+    pub fn solve(&mut self, game: &mut Game, deadline: Option<Instant>) -> Solution {
+        self.search.solve::<Vct>(game, self.depth, deadline)
+    }
+
+    /// The moves that leave the side to play with an open three.
+    fn threes(game: &Game) -> Vec<Position> {
+        let mut threes: Vec<Position> = open(game, &game.side_to_play, 2)
+            .into_iter()
+            .flat_map(|line| {
+                let last = line.len() - 1;
+                line.into_iter().take(last).skip(1)
+            })
+            .filter(|position| game.get(position) == Some(Cell::Playable))
+            .collect();
+        threes.sort();
+        threes.dedup();
+        threes
+    }
+}
+
+impl Forcing for Vct {
+    fn threats(game: &Game) -> Vec<Position> {
+        let mut moves = Vcf::fours(game);
+        for position in Vct::threes(game) {
+            if !moves.contains(&position) {
+                moves.push(position);
+            }
+        }
+        moves
+    }
+
+    /// An open three is stopped in it or at either end of it, or put off by
+    /// a four of the defender's own.
+    fn replies(game: &Game) -> Vec<Position> {
+        let defender = game.side_to_play.clone();
+        let threes = open(game, &defender.other(), 1);
+        if threes.is_empty() {
+            return vec![];
+        }
+        let mut replies: Vec<Position> = threes
+            .into_iter()
+            .flatten()
+            .chain(threats(game, &defender, 2).into_iter().flatten())
+            .filter(|position| game.get(position) == Some(Cell::Playable))
+            .collect();
+        replies.sort();
+        replies.dedup();
+        replies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{core::Side, heuristics::termination::Termination};
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    fn replay(game: &Game, sequence: &[Position]) -> Option<Termination> {
+        let mut game = game.clone();
+        for position in sequence {
+            game.play(position);
+        }
+        Termination::of(&game)
+    }
+
+    #[test]
+    fn test_vct_finds_vcf() -> Result<()> {
+        let (_, mut game) = Game::parse("9_/9_/4_o4_/9_/4_x4_/4_x4_/o3x5_/9_/9_ x")?;
+        game.set_win_length(5);
+        match Vct::new(10_000, 4).solve(&mut game, None) {
+            Solution::Won(sequence) => {
+                assert_eq!(replay(&game, &sequence), Some(Termination::Won(Side::X)));
+            }
+            solution => panic!("expected a win, got {:?}", solution),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_vct_finds_double_three() -> Result<()> {
+        let (_, mut game) = Game::parse("9_/9_/4_x4_/4_x4_/2_2x5_/9_/9_/7_o_/7_2o x")?;
+        game.set_win_length(5);
+        assert_eq!(Vcf::new(10_000).solve(&mut game, None), Solution::Refuted);
+        match Vct::new(100_000, 3).solve(&mut game, None) {
+            Solution::Won(sequence) => {
+                assert_eq!(replay(&game, &sequence), Some(Termination::Won(Side::X)));
+            }
+            solution => panic!("expected a win, got {:?}", solution),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_vct_refutes_lone_stones() -> Result<()> {
+        let (_, mut game) = Game::parse("9_/9_/9_/9_/4_x4_/4_o4_/9_/9_/9_ x")?;
+        game.set_win_length(5);
+        assert_eq!(
+            Vct::new(10_000, 4).solve(&mut game, None),
+            Solution::Refuted
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_vct_is_unknown_when_out_of_depth() -> Result<()> {
+        let (_, mut game) = Game::parse("9_/9_/4_x4_/4_x4_/2_2x5_/9_/9_/7_o_/7_2o x")?;
+        game.set_win_length(5);
+        assert_eq!(
+            Vct::new(100_000, 0).solve(&mut game, None),
+            Solution::Unknown
+        );
+        Ok(())
+    }
+}